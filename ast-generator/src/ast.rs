@@ -17,8 +17,8 @@ pub mod expressions {
 
         let mut tree_types: Vec<TreeType> = Vec::new();
 
-        writeln!(file, "{}", "use crate::error::*;")?;
-        writeln!(file, "{}", "use crate::token::*;")?;
+        writeln!(file, "use crate::error::*;")?;
+        writeln!(file, "use crate::token::*;")?;
 
         for t_type in types {
             let (base_class_name, args) = t_type.split_once(":").unwrap();
@@ -3,6 +3,7 @@ use std::io::{self, stdout, BufRead, Write};
 use clap::{arg, Command};
 
 use ast_generator::ast;
+use interpreter::checker::Checker;
 use interpreter::error::LaxError;
 use interpreter::interpreter::Interpreter;
 use interpreter::parser::Parser;
@@ -51,7 +52,7 @@ fn run_prompt() {
             if line.is_empty() {
                 break;
             }
-            let _ = run(&line.as_str());
+            let _ = run(line.as_str());
         } else {
             break;
         }
@@ -64,11 +65,17 @@ fn run(source: &str) -> Result<(), LaxError> {
     let scanner = Scanner::new(source.to_string());
     let tokens = scanner.scan_tokens()?;
     let mut parser = Parser::new(tokens.clone());
-    let interpreter = Interpreter {};
+    let interpreter = Interpreter::new();
     match parser.parse() {
         None => {}
         Some(expr) => {
-            interpreter.interpret(&expr)?;
+            // Type errors are reported when raised, like parse errors.
+            Checker::new().check(parser.operators.values(), &expr)?;
+            // Runtime errors are not reported when raised, since a script may catch them.
+            if let Err(err) = interpreter.interpret(&expr) {
+                err.report("");
+                return Err(err);
+            }
         }
     }
 
@@ -80,11 +87,20 @@ fn create_ast(outdir: &str) -> io::Result<()> {
     ast::expressions::define_ast(
         outdir,
         "Expr",
-        &vec![
+        &[
             "Binary    : Box<Expr> left, Token operator, Box<Expr> right",
             "Grouping  : Box<Expr> expression",
             "Literal   : Object value",
             "Unary     : Token operator,Box<Expr> right",
+            "Variable  : Token name",
+            "Match     : Token keyword, Box<Expr> value, Vec<MatchArm> arms",
+            "Get       : Box<Expr> object, Token name",
+            "Slice     : Box<Expr> object, Token bracket, Option<Box<Expr>> start, Option<Box<Expr>> end, Option<Box<Expr>> step",
+            "Throw     : Token keyword, Box<Expr> value",
+            "Try       : Box<Expr> body, Option<CatchClause> catch, Option<Box<Expr>> finally",
+            "Tuple     : Vec<Expr> elements",
+            "Set       : Vec<Expr> elements",
+            "Infix     : Box<Expr> left, Token operator, Box<Expr> right, Rc<InfixDecl> declaration",
         ],
     )?;
 
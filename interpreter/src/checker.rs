@@ -0,0 +1,455 @@
+//! This module contains the static type checker that runs before the interpreter
+//!
+//! Types come from annotations and from local inference over literals and
+//! operators. Everything else has type [`Type::Any`] and is left to the
+//! interpreter, so unannotated code keeps working as before.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{
+    error::LaxError,
+    expr::{Expr, ExprVisitor, InfixDecl, Pattern},
+    token::{Object, Token, TokenType},
+    types::Type,
+};
+
+#[derive(Default)]
+pub struct Checker {
+    scopes: RefCell<Vec<HashMap<String, Type>>>,
+    /// Set while checking the body of a try expression with a catch clause.
+    catching: Cell<bool>,
+}
+
+impl Checker {
+    pub fn new() -> Self {
+        Self {
+            scopes: RefCell::new(vec![]),
+            catching: Cell::new(false),
+        }
+    }
+
+    /// Checks the bodies of the declared operators, then the program expression.
+    ///
+    /// Returns the type of the program expression.
+    pub fn check<'a>(
+        &self,
+        operators: impl IntoIterator<Item = &'a Rc<InfixDecl>>,
+        expr: &Expr,
+    ) -> Result<Type, LaxError> {
+        let mut operators: Vec<&Rc<InfixDecl>> = operators.into_iter().collect();
+        operators.sort_by_key(|declaration| declaration.symbol.line);
+        for declaration in operators {
+            self.check_operator(declaration)?;
+        }
+        self.check_expr(expr)
+    }
+
+    fn check_expr(&self, expr: &Expr) -> Result<Type, LaxError> {
+        expr.accept(self)
+    }
+
+    fn check_operator(&self, declaration: &InfixDecl) -> Result<(), LaxError> {
+        let found = self.in_scope(
+            &[
+                (&declaration.left, declaration.left_type.clone()),
+                (&declaration.right, declaration.right_type.clone()),
+            ],
+            || self.check_expr(&declaration.body),
+        )?;
+
+        if declaration.return_type.accepts(&found) {
+            Ok(())
+        } else {
+            Err(LaxError::type_error(
+                declaration.symbol.clone(),
+                &format!(
+                    "Operator '{}' must return {}, found {}.",
+                    declaration.symbol.lexeme, declaration.return_type, found
+                ),
+            ))
+        }
+    }
+
+    /// Runs `check` in a new scope that binds `names`.
+    fn in_scope<T>(
+        &self,
+        names: &[(&Token, Type)],
+        check: impl FnOnce() -> Result<T, LaxError>,
+    ) -> Result<T, LaxError> {
+        let scope = names
+            .iter()
+            .map(|(name, t_type)| (name.lexeme.clone(), t_type.clone()))
+            .collect();
+        self.scopes.borrow_mut().push(scope);
+        let result = check();
+        self.scopes.borrow_mut().pop();
+        result
+    }
+
+    /// Returns the names a pattern binds when it matches a value of type `value`.
+    fn bindings<'a>(&self, pattern: &'a Pattern, value: &Type, names: &mut Vec<(&'a Token, Type)>) {
+        match pattern {
+            Pattern::Binding(name) => names.push((name, value.clone())),
+            Pattern::Tuple(patterns) => {
+                for pattern in patterns {
+                    self.bindings(pattern, &Type::Any, names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the type of the values of type `value` that `pattern` matches.
+    fn matched(&self, pattern: &Pattern, value: &Type) -> Type {
+        match pattern {
+            Pattern::Literal(literal) => Type::of(literal),
+            Pattern::Range(_) => Type::Number,
+            Pattern::Type(name) => Type::named(&name.lexeme).unwrap_or_else(|| value.clone()),
+            Pattern::Tuple(_) => Type::Tuple,
+            Pattern::Wildcard | Pattern::Binding(_) => value.clone(),
+        }
+    }
+
+    /// Returns the type of the values left for later arms by an arm without guard.
+    ///
+    /// Taking nil from an `Option<T>` leaves a `T`, and taking every value leaves [`Type::Never`].
+    fn remaining(&self, pattern: &Pattern, value: Type) -> Type {
+        let taken = match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => return Type::Never,
+            Pattern::Literal(Object::Nil) => Type::Nil,
+            Pattern::Type(name) => match Type::named(&name.lexeme) {
+                Some(taken) => taken,
+                None => return value,
+            },
+            _ => return value,
+        };
+        match value {
+            Type::Option(inner) if taken == Type::Nil => *inner,
+            value if value == taken => Type::Never,
+            value => value,
+        }
+    }
+
+    /// Checks that `found` may be used as an operand of `operator` that expects `expected`.
+    fn operand(&self, operator: &Token, expected: &Type, found: &Type) -> Result<(), LaxError> {
+        if let (Type::Option(_), false) = (found, matches!(expected, Type::Option(_))) {
+            if *expected != Type::Any {
+                return Err(LaxError::type_error(
+                    operator.clone(),
+                    &format!(
+                        "Operand of '{}' has type {} and may be nil. Check it with match first.",
+                        operator.lexeme, found
+                    ),
+                ));
+            }
+        }
+        if expected.accepts(found) {
+            Ok(())
+        } else {
+            Err(LaxError::type_error(
+                operator.clone(),
+                &format!(
+                    "Expected {} operand for '{}', found {}.",
+                    expected, operator.lexeme, found
+                ),
+            ))
+        }
+    }
+
+    /// Infers type arguments from an operand of type `found` for a parameter of type `expected`.
+    ///
+    /// The first operand that mentions a type parameter decides its argument.
+    fn infer(&self, expected: &Type, found: &Type, arguments: &mut HashMap<String, Type>) {
+        match (expected, found) {
+            (_, Type::Any | Type::Never) | (Type::Option(_), Type::Nil) => {}
+            (Type::Param(name), found) => {
+                arguments
+                    .entry(name.clone())
+                    .or_insert_with(|| found.clone());
+            }
+            (Type::Option(expected), Type::Option(found)) => self.infer(expected, found, arguments),
+            (Type::Option(expected), found) => self.infer(expected, found, arguments),
+            _ => {}
+        }
+    }
+
+    /// Checks an operand of a built-in operator.
+    ///
+    /// Built-in operators fail with a catchable error at runtime, so nothing is reported
+    /// while checking the body of a try expression with a catch clause.
+    fn builtin_operand(
+        &self,
+        operator: &Token,
+        expected: &Type,
+        found: &Type,
+    ) -> Result<(), LaxError> {
+        if self.catching.get() {
+            Ok(())
+        } else {
+            self.operand(operator, expected, found)
+        }
+    }
+}
+
+impl ExprVisitor<Type> for Checker {
+    fn visit_binary_expr(&self, expr: &crate::expr::BinaryExpr) -> Result<Type, LaxError> {
+        let left = self.check_expr(&expr.left)?;
+        let right = self.check_expr(&expr.right)?;
+        let operator = &expr.operator;
+
+        match operator.t_type {
+            TokenType::Pipe | TokenType::Ampersand => {
+                self.builtin_operand(operator, &Type::Set, &left)?;
+                self.builtin_operand(operator, &Type::Set, &right)?;
+                Ok(Type::Set)
+            }
+            TokenType::Minus if left == Type::Set || right == Type::Set => {
+                self.builtin_operand(operator, &Type::Set, &left)?;
+                self.builtin_operand(operator, &Type::Set, &right)?;
+                Ok(Type::Set)
+            }
+            TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+                if left == Type::Set || right == Type::Set =>
+            {
+                self.builtin_operand(operator, &Type::Set, &left)?;
+                self.builtin_operand(operator, &Type::Set, &right)?;
+                Ok(Type::Bool)
+            }
+            TokenType::Minus | TokenType::Star | TokenType::Slash => {
+                self.builtin_operand(operator, &Type::Number, &left)?;
+                self.builtin_operand(operator, &Type::Number, &right)?;
+                Ok(Type::Number)
+            }
+            TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual => {
+                self.builtin_operand(operator, &Type::Number, &left)?;
+                self.builtin_operand(operator, &Type::Number, &right)?;
+                Ok(Type::Bool)
+            }
+            TokenType::DotDot | TokenType::DotDotEqual => {
+                self.builtin_operand(operator, &Type::Number, &left)?;
+                self.builtin_operand(operator, &Type::Number, &right)?;
+                Ok(Type::Range)
+            }
+            TokenType::Plus => match (&left, &right) {
+                (Type::Any | Type::Never, Type::Any | Type::Never) => Ok(Type::Any),
+                (Type::Number | Type::String, _) => {
+                    self.builtin_operand(operator, &left, &right)?;
+                    Ok(left)
+                }
+                (Type::Option(inner), _) => {
+                    self.builtin_operand(operator, inner, &left)?;
+                    Ok(Type::Any)
+                }
+                (Type::Any | Type::Never, Type::Number | Type::String) => Ok(right),
+                _ if self.catching.get() => Ok(Type::Any),
+                (Type::Any | Type::Never, _) => Err(LaxError::type_error(
+                    operator.clone(),
+                    &format!(
+                        "Expected Number or String operand for '+', found {}.",
+                        right
+                    ),
+                )),
+                _ => Err(LaxError::type_error(
+                    operator.clone(),
+                    &format!("Expected Number or String operand for '+', found {}.", left),
+                )),
+            },
+            TokenType::EqualEqual | TokenType::BangEqual => {
+                // Ranges, tuples and sets compare unequal to values of other types.
+                let structural = matches!(left, Type::Range | Type::Tuple | Type::Set);
+                if !structural && !left.accepts(&right) && !right.accepts(&left) {
+                    self.builtin_operand(operator, &left, &right)?;
+                }
+                Ok(Type::Bool)
+            }
+            TokenType::In | TokenType::BangIn => {
+                match &right {
+                    Type::Range => self.builtin_operand(operator, &Type::Number, &left)?,
+                    Type::String => self.builtin_operand(operator, &Type::String, &left)?,
+                    Type::Set | Type::Any | Type::Never => {}
+                    _ if self.catching.get() => {}
+                    _ => {
+                        return Err(LaxError::type_error(
+                            operator.clone(),
+                            &format!(
+                                "Expected String, Range or Set operand for '{}', found {}.",
+                                operator.lexeme, right
+                            ),
+                        ))
+                    }
+                }
+                Ok(Type::Bool)
+            }
+            _ => Ok(Type::Any),
+        }
+    }
+
+    fn visit_grouping_expr(&self, expr: &crate::expr::GroupingExpr) -> Result<Type, LaxError> {
+        self.check_expr(&expr.expression)
+    }
+
+    fn visit_literal_expr(&self, expr: &crate::expr::LiteralExpr) -> Result<Type, LaxError> {
+        Ok(match &expr.value {
+            Some(value) => Type::of(value),
+            None => Type::Nil,
+        })
+    }
+
+    fn visit_unary_expr(&self, expr: &crate::expr::UnaryExpr) -> Result<Type, LaxError> {
+        let right = self.check_expr(&expr.right)?;
+
+        match expr.operator.t_type {
+            TokenType::Minus => {
+                self.builtin_operand(&expr.operator, &Type::Number, &right)?;
+                Ok(Type::Number)
+            }
+            TokenType::Plus => {
+                self.builtin_operand(&expr.operator, &Type::String, &right)?;
+                Ok(Type::Number)
+            }
+            TokenType::Bang | TokenType::Not => Ok(Type::Bool),
+            _ => Ok(Type::Any),
+        }
+    }
+
+    fn visit_variable_expr(&self, expr: &crate::expr::VariableExpr) -> Result<Type, LaxError> {
+        Ok(self
+            .scopes
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&expr.name.lexeme))
+            .cloned()
+            .unwrap_or(Type::Any))
+    }
+
+    fn visit_match_expr(&self, expr: &crate::expr::MatchExpr) -> Result<Type, LaxError> {
+        let value = self.check_expr(&expr.value)?;
+        let mut result = Type::Never;
+        // Later arms only see the values that arms without guard have not taken.
+        let mut narrowed = value.clone();
+
+        for arm in &expr.arms {
+            let mut names = vec![];
+            // A matched variable has the type of the values that reach the arm.
+            if let Expr::Variable(variable) = &*expr.value {
+                names.push((&variable.name, self.matched(&arm.pattern, &narrowed)));
+            }
+            self.bindings(&arm.pattern, &narrowed, &mut names);
+            let body = self.in_scope(&names, || {
+                if let Some(guard) = &arm.guard {
+                    self.check_expr(guard)?;
+                }
+                self.check_expr(&arm.body)
+            })?;
+            result = result.join(&body);
+
+            if arm.guard.is_none() {
+                narrowed = self.remaining(&arm.pattern, narrowed);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn visit_get_expr(&self, expr: &crate::expr::GetExpr) -> Result<Type, LaxError> {
+        let object = self.check_expr(&expr.object)?;
+
+        match object {
+            Type::Any | Type::Never => Ok(Type::Any),
+            Type::Error => match expr.name.lexeme.as_str() {
+                "message" | "type" => Ok(Type::String),
+                "line" => Ok(Type::Number),
+                _ => Err(LaxError::type_error(
+                    expr.name.clone(),
+                    "Undefined property of Error.",
+                )),
+            },
+            _ => Err(LaxError::type_error(
+                expr.name.clone(),
+                &format!("Expected Error, found {}.", object),
+            )),
+        }
+    }
+
+    fn visit_slice_expr(&self, expr: &crate::expr::SliceExpr) -> Result<Type, LaxError> {
+        let object = self.check_expr(&expr.object)?;
+        // A bound may be nil, which leaves it out.
+        let bound = Type::Option(Box::new(Type::Number));
+        for found in [&expr.start, &expr.end, &expr.step].into_iter().flatten() {
+            let found = self.check_expr(found)?;
+            self.operand(&expr.bracket, &bound, &found)?;
+        }
+
+        match object {
+            Type::String | Type::Tuple | Type::Any | Type::Never => Ok(object),
+            _ => Err(LaxError::type_error(
+                expr.bracket.clone(),
+                &format!("Expected String or Tuple to slice, found {}.", object),
+            )),
+        }
+    }
+
+    fn visit_throw_expr(&self, expr: &crate::expr::ThrowExpr) -> Result<Type, LaxError> {
+        self.check_expr(&expr.value)?;
+        Ok(Type::Never)
+    }
+
+    fn visit_try_expr(&self, expr: &crate::expr::TryExpr) -> Result<Type, LaxError> {
+        let catching = self.catching.get();
+        self.catching.set(catching || expr.catch.is_some());
+        let body = self.check_expr(&expr.body);
+        self.catching.set(catching);
+        let mut result = body?;
+
+        if let Some(catch) = &expr.catch {
+            // Anything can be thrown, so the caught value is dynamic.
+            let body =
+                self.in_scope(&[(&catch.name, Type::Any)], || self.check_expr(&catch.body))?;
+            result = result.join(&body);
+        }
+        if let Some(finally) = &expr.finally {
+            self.check_expr(finally)?;
+        }
+
+        Ok(result)
+    }
+
+    fn visit_tuple_expr(&self, expr: &crate::expr::TupleExpr) -> Result<Type, LaxError> {
+        for element in &expr.elements {
+            self.check_expr(element)?;
+        }
+        Ok(Type::Tuple)
+    }
+
+    fn visit_set_expr(&self, expr: &crate::expr::SetExpr) -> Result<Type, LaxError> {
+        for element in &expr.elements {
+            self.check_expr(element)?;
+        }
+        Ok(Type::Set)
+    }
+
+    fn visit_infix_expr(&self, expr: &crate::expr::InfixExpr) -> Result<Type, LaxError> {
+        let left = self.check_expr(&expr.left)?;
+        let right = self.check_expr(&expr.right)?;
+        let declaration = &expr.declaration;
+
+        let mut arguments = HashMap::new();
+        self.infer(&declaration.left_type, &left, &mut arguments);
+        self.infer(&declaration.right_type, &right, &mut arguments);
+
+        let left_type = declaration.left_type.instantiate(&arguments);
+        let right_type = declaration.right_type.instantiate(&arguments);
+        self.operand(&expr.operator, &left_type, &left)?;
+        self.operand(&expr.operator, &right_type, &right)?;
+        Ok(declaration.return_type.instantiate(&arguments))
+    }
+}
@@ -0,0 +1,44 @@
+//! This module contains the compile time evaluation of `const` declarations
+
+use crate::{
+    error::LaxError, expr::Expr, interpreter::Interpreter, printer::AstPrinter, token::Object,
+    token::Token,
+};
+
+/// Evaluates the value of the constant `name`.
+///
+/// Constant expressions are built from literals, other constants, tuples, sets, slices and
+/// operators, including operators declared with `infix`, which have no side effects.
+pub fn evaluate(name: &Token, expr: &Expr) -> Result<Object, LaxError> {
+    if let Some(expr) = non_constant(expr) {
+        let printed = AstPrinter::new().print(expr)?;
+        return Err(LaxError::parse_error(
+            name.clone(),
+            &format!("Expression '{}' is not constant.", printed),
+        ));
+    }
+
+    Interpreter::new()
+        .evaluate(expr)
+        .map_err(|err| LaxError::parse_error(name.clone(), &err.message))
+}
+
+/// Returns the first sub-expression of `expr` that can't be evaluated at compile time.
+fn non_constant(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Literal(_) => None,
+        Expr::Grouping(expr) => non_constant(&expr.expression),
+        Expr::Unary(expr) => non_constant(&expr.right),
+        Expr::Binary(expr) => non_constant(&expr.left).or_else(|| non_constant(&expr.right)),
+        Expr::Infix(expr) => non_constant(&expr.left).or_else(|| non_constant(&expr.right)),
+        Expr::Tuple(expr) => expr.elements.iter().find_map(non_constant),
+        Expr::Set(expr) => expr.elements.iter().find_map(non_constant),
+        Expr::Slice(expr) => non_constant(&expr.object).or_else(|| {
+            [&expr.start, &expr.end, &expr.step]
+                .into_iter()
+                .flatten()
+                .find_map(|bound| non_constant(bound))
+        }),
+        _ => Some(expr),
+    }
+}
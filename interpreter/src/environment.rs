@@ -0,0 +1,41 @@
+//! This module contains the storage for names bound while evaluating expressions
+
+use std::collections::HashMap;
+
+use crate::token::Object;
+
+/// A stack of scopes. The innermost scope is the last one.
+#[derive(Default)]
+pub struct Environment {
+    scopes: Vec<HashMap<String, Object>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self { scopes: vec![] }
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Binds `name` in the innermost scope.
+    pub fn define(&mut self, name: &str, value: Object) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), value);
+        }
+    }
+
+    /// Looks `name` up starting at the innermost scope.
+    pub fn get(&self, name: &str) -> Option<Object> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+}
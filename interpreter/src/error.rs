@@ -1,10 +1,42 @@
-use crate::token::{Token, TokenType};
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::token::{ErrorObject, Object, Token, TokenType};
+
+/// The category of an error. Runtime kinds are visible to scripts through the
+/// `type` property of a caught error.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorKind {
+    Syntax,
+    Type,
+    Name,
+    Value,
+    Match,
+    /// A value raised by a `throw` expression.
+    Thrown,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Syntax => write!(f, "SyntaxError"),
+            ErrorKind::Type => write!(f, "TypeError"),
+            ErrorKind::Name => write!(f, "NameError"),
+            ErrorKind::Value => write!(f, "ValueError"),
+            ErrorKind::Match => write!(f, "MatchError"),
+            ErrorKind::Thrown => write!(f, "Error"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct LaxError {
     pub token: Option<Token>,
     pub line: usize,
     pub message: String,
+    pub kind: ErrorKind,
+    /// The value passed to `throw`, if the error was raised by a script.
+    pub value: Option<Box<Object>>,
 }
 
 impl LaxError {
@@ -13,6 +45,8 @@ impl LaxError {
             token: None,
             line,
             message,
+            kind: ErrorKind::Syntax,
+            value: None,
         };
         err.report("");
         err
@@ -23,11 +57,64 @@ impl LaxError {
             token: Some(token.clone()),
             line: token.clone().line,
             message: message.to_owned(),
+            kind: ErrorKind::Syntax,
+            value: None,
+        };
+        err.report("");
+        err
+    }
+
+    /// Creates an error found by the type checker.
+    pub fn type_error(token: Token, message: &str) -> LaxError {
+        let err = LaxError {
+            token: Some(token.clone()),
+            line: token.line,
+            message: message.to_owned(),
+            kind: ErrorKind::Type,
+            value: None,
         };
         err.report("");
         err
     }
 
+    /// Creates an error raised while interpreting.
+    ///
+    /// Unlike the other constructors this does not report the error, because a
+    /// script can still catch it. Uncaught runtime errors are reported by the caller.
+    pub fn runtime(kind: ErrorKind, line: usize, message: String) -> LaxError {
+        LaxError {
+            token: None,
+            line,
+            message,
+            kind,
+            value: None,
+        }
+    }
+
+    /// Creates the error raised by `throw value`.
+    pub fn throw(line: usize, value: Object) -> LaxError {
+        LaxError {
+            token: None,
+            line,
+            message: format!("Uncaught {}", value),
+            kind: ErrorKind::Thrown,
+            value: Some(Box::new(value)),
+        }
+    }
+
+    /// Returns the value a `catch` clause binds for this error: the thrown value,
+    /// or an error object describing a built-in error.
+    pub fn to_object(&self) -> Object {
+        match &self.value {
+            Some(value) => *value.clone(),
+            None => Object::Error(Box::new(ErrorObject {
+                kind: self.kind.to_string(),
+                message: self.message.clone(),
+                line: self.line,
+            })),
+        }
+    }
+
     pub fn report(&self, loc: &str) {
         if let Some(token) = &self.token {
             if token.is(TokenType::EOF) {
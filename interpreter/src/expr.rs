@@ -1,11 +1,24 @@
+use std::rc::Rc;
+
 use crate::error::*;
 use crate::token::*;
+use crate::types::Type;
 
+#[derive(Clone)]
 pub enum Expr {
     Binary(BinaryExpr),
     Grouping(GroupingExpr),
     Literal(LiteralExpr),
     Unary(UnaryExpr),
+    Variable(VariableExpr),
+    Match(MatchExpr),
+    Get(GetExpr),
+    Slice(SliceExpr),
+    Throw(ThrowExpr),
+    Try(TryExpr),
+    Tuple(TupleExpr),
+    Set(SetExpr),
+    Infix(InfixExpr),
 }
 impl Expr {
     pub fn accept<T>(&self, expr_visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
@@ -14,29 +27,151 @@ impl Expr {
             Expr::Grouping(v) => v.accept(expr_visitor),
             Expr::Literal(v) => v.accept(expr_visitor),
             Expr::Unary(v) => v.accept(expr_visitor),
+            Expr::Variable(v) => v.accept(expr_visitor),
+            Expr::Match(v) => v.accept(expr_visitor),
+            Expr::Get(v) => v.accept(expr_visitor),
+            Expr::Slice(v) => v.accept(expr_visitor),
+            Expr::Throw(v) => v.accept(expr_visitor),
+            Expr::Try(v) => v.accept(expr_visitor),
+            Expr::Tuple(v) => v.accept(expr_visitor),
+            Expr::Set(v) => v.accept(expr_visitor),
+            Expr::Infix(v) => v.accept(expr_visitor),
         }
     }
 }
 
+#[derive(Clone)]
 pub struct BinaryExpr {
     pub left: Box<Expr>,
     pub operator: Token,
     pub right: Box<Expr>,
 }
 
+#[derive(Clone)]
 pub struct GroupingExpr {
     pub expression: Box<Expr>,
 }
 
+#[derive(Clone)]
 pub struct LiteralExpr {
     pub value: Option<Object>,
 }
 
+#[derive(Clone)]
 pub struct UnaryExpr {
     pub operator: Token,
     pub right: Box<Expr>,
 }
 
+#[derive(Clone)]
+pub struct VariableExpr {
+    pub name: Token,
+}
+
+#[derive(Clone)]
+pub struct MatchExpr {
+    pub keyword: Token,
+    pub value: Box<Expr>,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Expr,
+}
+
+/// A pattern on the left hand side of a match arm.
+#[derive(Clone)]
+pub enum Pattern {
+    /// `_`, matches everything without binding.
+    Wildcard,
+    /// A literal value that has to be equal to the matched value.
+    Literal(Object),
+    /// `start..end` or `start..=end` over numbers.
+    Range(Range),
+    /// `is Number`, matches if the value has the given type.
+    Type(Token),
+    /// An identifier that binds the matched value for the guard and the body.
+    Binding(Token),
+    /// `(a, b)`, matches a tuple of the same length whose elements match.
+    Tuple(Vec<Pattern>),
+}
+
+#[derive(Clone)]
+pub struct GetExpr {
+    pub object: Box<Expr>,
+    pub name: Token,
+}
+
+/// `object[start:end:step]`, where every bound may be left out.
+#[derive(Clone)]
+pub struct SliceExpr {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub start: Option<Box<Expr>>,
+    pub end: Option<Box<Expr>>,
+    pub step: Option<Box<Expr>>,
+}
+
+#[derive(Clone)]
+pub struct ThrowExpr {
+    pub keyword: Token,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone)]
+pub struct TryExpr {
+    pub body: Box<Expr>,
+    pub catch: Option<CatchClause>,
+    pub finally: Option<Box<Expr>>,
+}
+
+/// `catch (name) { body }`
+#[derive(Clone)]
+pub struct CatchClause {
+    pub name: Token,
+    pub body: Box<Expr>,
+}
+
+/// `(a, b, c)`, told apart from a grouping by the comma.
+#[derive(Clone)]
+pub struct TupleExpr {
+    pub elements: Vec<Expr>,
+}
+
+/// `{a, b, c}`
+#[derive(Clone)]
+pub struct SetExpr {
+    pub elements: Vec<Expr>,
+}
+
+/// A use of an operator declared with `infix`.
+#[derive(Clone)]
+pub struct InfixExpr {
+    pub left: Box<Expr>,
+    pub operator: Token,
+    pub right: Box<Expr>,
+    pub declaration: Rc<InfixDecl>,
+}
+
+/// `infix precedence symbol <T>(left: Type, right: Type): Type { body }`
+///
+/// Parameters and results without annotation have type [`Type::Any`]. The type
+/// parameters are optional and inferred from the operands at each use.
+pub struct InfixDecl {
+    pub symbol: Token,
+    pub precedence: u8,
+    pub type_params: Vec<Token>,
+    pub left: Token,
+    pub left_type: Type,
+    pub right: Token,
+    pub right_type: Type,
+    pub return_type: Type,
+    pub body: Expr,
+}
+
 pub struct DoubleExpr {
     pub left: Box<Expr>,
     pub operator_one: Token,
@@ -48,6 +183,15 @@ pub trait ExprVisitor<T> {
     fn visit_grouping_expr(&self, expr: &GroupingExpr) -> Result<T, LaxError>;
     fn visit_literal_expr(&self, expr: &LiteralExpr) -> Result<T, LaxError>;
     fn visit_unary_expr(&self, expr: &UnaryExpr) -> Result<T, LaxError>;
+    fn visit_variable_expr(&self, expr: &VariableExpr) -> Result<T, LaxError>;
+    fn visit_match_expr(&self, expr: &MatchExpr) -> Result<T, LaxError>;
+    fn visit_get_expr(&self, expr: &GetExpr) -> Result<T, LaxError>;
+    fn visit_slice_expr(&self, expr: &SliceExpr) -> Result<T, LaxError>;
+    fn visit_throw_expr(&self, expr: &ThrowExpr) -> Result<T, LaxError>;
+    fn visit_try_expr(&self, expr: &TryExpr) -> Result<T, LaxError>;
+    fn visit_tuple_expr(&self, expr: &TupleExpr) -> Result<T, LaxError>;
+    fn visit_set_expr(&self, expr: &SetExpr) -> Result<T, LaxError>;
+    fn visit_infix_expr(&self, expr: &InfixExpr) -> Result<T, LaxError>;
 }
 
 impl BinaryExpr {
@@ -73,3 +217,57 @@ impl UnaryExpr {
         visitor.visit_unary_expr(self)
     }
 }
+
+impl VariableExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_variable_expr(self)
+    }
+}
+
+impl MatchExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_match_expr(self)
+    }
+}
+
+impl GetExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_get_expr(self)
+    }
+}
+
+impl SliceExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_slice_expr(self)
+    }
+}
+
+impl ThrowExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_throw_expr(self)
+    }
+}
+
+impl TryExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_try_expr(self)
+    }
+}
+
+impl TupleExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_tuple_expr(self)
+    }
+}
+
+impl SetExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_set_expr(self)
+    }
+}
+
+impl InfixExpr {
+    fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, LaxError> {
+        visitor.visit_infix_expr(self)
+    }
+}
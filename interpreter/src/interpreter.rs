@@ -1,44 +1,272 @@
-//! This module contains the logic to evaluate expressions 
+//! This module contains the logic to evaluate expressions
+
+use std::cell::RefCell;
 
 use crate::{
-    error::LaxError,
-    expr::{Expr, ExprVisitor},
-    token::{Object, TokenType},
+    environment::Environment,
+    error::{ErrorKind, LaxError},
+    expr::{Expr, ExprVisitor, MatchArm, Pattern},
+    token::{Object, Range, Token, TokenType},
 };
 
-pub struct Interpreter {}
+#[derive(Default)]
+pub struct Interpreter {
+    environment: RefCell<Environment>,
+}
 
 impl Interpreter {
+    pub fn new() -> Self {
+        Self {
+            environment: RefCell::new(Environment::new()),
+        }
+    }
+
     pub fn interpret(&self, expr: &Expr) -> Result<Object, LaxError> {
-        let value = self.evaluate(&expr)?;
+        let value = self.evaluate(expr)?;
         println!("{}", value);
         Ok(value)
     }
 
     /// Evaluates the given expression by calling the appropriate visitor method.
-    /// 
+    ///
     /// Returns the result of the evaluation.
-    fn evaluate(&self, expr: &Expr) -> Result<Object, LaxError> {
+    pub(crate) fn evaluate(&self, expr: &Expr) -> Result<Object, LaxError> {
         expr.accept(self)
     }
 
     /// Checks if the given object is truthy or falsey.
     /// # Returns
     /// `false` if the object is nil
-    /// 
+    ///
     /// `true` or `false` depending on the [`Object::Bool`] value
-    /// 
+    ///
     /// `true` or `false` for [`Object::Str`] if the string is not empty
-    /// 
+    ///
     /// `true` for everything else
     fn is_truthy(&self, object: &Object) -> bool {
         match object {
             Object::Nil => false,
             Object::Bool(bool) => *bool,
-            Object::Str(str) => str.is_empty(),
+            Object::Str(str) => !str.is_empty(),
             _ => true,
         }
     }
+
+    /// Compares two values of the same type. Values of different types are never equal.
+    fn is_equal(&self, left: &Object, right: &Object) -> bool {
+        match (left, right) {
+            (Object::Num(left), Object::Num(right)) => left == right,
+            (Object::Str(left), Object::Str(right)) => left == right,
+            (Object::Bool(left), Object::Bool(right)) => left == right,
+            (Object::Nil, Object::Nil) => true,
+            (Object::Range(left), Object::Range(right)) => left == right,
+            (Object::Tuple(left), Object::Tuple(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right.iter())
+                        .all(|(left, right)| self.is_equal(left, right))
+            }
+            (Object::Set(left), Object::Set(right)) => {
+                left.len() == right.len() && self.is_subset(left, right)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the name of the type of the given object as used by `is` patterns.
+    fn type_name(&self, object: &Object) -> &'static str {
+        match object {
+            Object::Num(_) => "Number",
+            Object::Str(_) => "String",
+            Object::Bool(_) | Object::True | Object::False => "Bool",
+            Object::Nil => "Nil",
+            Object::Error(_) => "Error",
+            Object::Range(_) => "Range",
+            Object::Tuple(_) => "Tuple",
+            Object::Set(_) => "Set",
+        }
+    }
+
+    fn set_contains(&self, elements: &[Object], value: &Object) -> bool {
+        elements.iter().any(|element| self.is_equal(element, value))
+    }
+
+    fn is_subset(&self, left: &[Object], right: &[Object]) -> bool {
+        left.iter().all(|element| self.set_contains(right, element))
+    }
+
+    /// Creates the error for a built-in binary operator applied to operands it doesn't support.
+    fn invalid_operands(&self, operator: &Token, left: &Object, right: &Object) -> LaxError {
+        LaxError::runtime(
+            ErrorKind::Type,
+            operator.line,
+            format!(
+                "Invalid operands for {}: {} and {}",
+                operator.lexeme, left, right
+            ),
+        )
+    }
+
+    /// Applies `operator` to a set operand, keeping the elements in insertion order.
+    ///
+    /// Returns `None` if `operator` is not a set operator for these operands.
+    fn set_operation(&self, operator: &TokenType, left: &Object, right: &Object) -> Option<Object> {
+        let elements = match right {
+            Object::Set(elements) => elements,
+            _ => return None,
+        };
+        match operator {
+            TokenType::In => return Some(Object::from(self.set_contains(elements, left))),
+            TokenType::BangIn => return Some(Object::from(!self.set_contains(elements, left))),
+            _ => {}
+        }
+
+        let (left, right) = match left {
+            Object::Set(left) => (left, elements),
+            _ => return None,
+        };
+        Some(match operator {
+            TokenType::Pipe => {
+                let mut union = left.clone();
+                for element in right {
+                    if !self.set_contains(&union, element) {
+                        union.push(element.clone());
+                    }
+                }
+                Object::Set(union)
+            }
+            TokenType::Ampersand => Object::Set(
+                left.iter()
+                    .filter(|element| self.set_contains(right, element))
+                    .cloned()
+                    .collect(),
+            ),
+            TokenType::Minus => Object::Set(
+                left.iter()
+                    .filter(|element| !self.set_contains(right, element))
+                    .cloned()
+                    .collect(),
+            ),
+            TokenType::LessEqual => Object::from(self.is_subset(left, right)),
+            TokenType::Less => {
+                Object::from(left.len() < right.len() && self.is_subset(left, right))
+            }
+            TokenType::GreaterEqual => Object::from(self.is_subset(right, left)),
+            TokenType::Greater => {
+                Object::from(right.len() < left.len() && self.is_subset(right, left))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Evaluates a slice bound, which must be a whole number. `nil` leaves the bound out.
+    fn slice_bound(
+        &self,
+        bound: &Option<Box<Expr>>,
+        bracket: &Token,
+    ) -> Result<Option<i64>, LaxError> {
+        let value = match bound {
+            Some(bound) => self.evaluate(bound)?,
+            None => return Ok(None),
+        };
+        match value {
+            Object::Num(num) if num.fract() == 0.0 => Ok(Some(num as i64)),
+            Object::Nil => Ok(None),
+            _ => Err(LaxError::runtime(
+                ErrorKind::Type,
+                bracket.line,
+                format!("Slice indices must be whole numbers, got {}", value),
+            )),
+        }
+    }
+
+    /// Returns the positions `[start:end:step]` selects from a sequence of `len` elements.
+    ///
+    /// Negative bounds count from the end and out of range bounds are clamped, as in Python.
+    fn slice_indices(
+        &self,
+        len: usize,
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    ) -> Vec<usize> {
+        let len = len as i64;
+        // A backwards slice may stop before the first element, at -1.
+        let (lowest, highest) = if step > 0 { (0, len) } else { (-1, len - 1) };
+        let bound = |index: Option<i64>, default: i64| match index {
+            Some(index) if index < 0 => (index + len).clamp(lowest, highest),
+            Some(index) => index.clamp(lowest, highest),
+            None => default,
+        };
+        let (mut index, end) = if step > 0 {
+            (bound(start, lowest), bound(end, highest))
+        } else {
+            (bound(start, highest), bound(end, lowest))
+        };
+
+        let mut indices = vec![];
+        while (step > 0 && index < end) || (step < 0 && index > end) {
+            indices.push(index as usize);
+            // A step larger than the sequence can overflow; no index lies beyond it anyway.
+            index = match index.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        indices
+    }
+
+    fn pattern_matches(&self, pattern: &Pattern, value: &Object) -> bool {
+        match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => true,
+            Pattern::Literal(literal) => self.is_equal(literal, value),
+            Pattern::Range(range) => match value {
+                Object::Num(num) => range.contains(*num),
+                _ => false,
+            },
+            Pattern::Type(name) => self.type_name(value) == name.lexeme,
+            Pattern::Tuple(patterns) => match value {
+                Object::Tuple(elements) => {
+                    patterns.len() == elements.len()
+                        && patterns
+                            .iter()
+                            .zip(elements.iter())
+                            .all(|(pattern, element)| self.pattern_matches(pattern, element))
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Defines the names bound by a pattern that matched `value` in the current scope.
+    fn bind_pattern(&self, pattern: &Pattern, value: &Object) {
+        match (pattern, value) {
+            (Pattern::Binding(name), _) => self
+                .environment
+                .borrow_mut()
+                .define(&name.lexeme, value.clone()),
+            (Pattern::Tuple(patterns), Object::Tuple(elements)) => {
+                for (pattern, element) in patterns.iter().zip(elements.iter()) {
+                    self.bind_pattern(pattern, element);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Evaluates the guard and the body of an arm whose pattern already matched.
+    ///
+    /// Returns `None` if the guard rejected the value.
+    fn evaluate_arm(&self, arm: &MatchArm) -> Result<Option<Object>, LaxError> {
+        if let Some(guard) = &arm.guard {
+            let condition = self.evaluate(guard)?;
+            if !self.is_truthy(&condition) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self.evaluate(&arm.body)?))
+    }
 }
 
 impl ExprVisitor<Object> for Interpreter {
@@ -49,199 +277,105 @@ impl ExprVisitor<Object> for Interpreter {
         let left = self.evaluate(&expr.left)?;
         let right = self.evaluate(&expr.right)?;
 
-        match &expr.operator.t_type {
-            TokenType::Minus => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::Num(left - right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for -: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for -: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::Slash => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::Num(left / right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for /: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for /: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::Star => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::Num(left * right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for *: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for *: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::Plus => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::Num(left + right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for +: {:?} and {:?}", left, right),
-                    )),
-                },
-                Object::Str(left) => match right {
-                    Object::Str(right) => Ok(Object::Str(format!("{}{}", left, right))),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for +: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for +: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::Greater => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left > right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for >: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for >: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::GreaterEqual => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left >= right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for >=: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for >=: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::Less => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left < right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for <: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for <: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::LessEqual => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left <= right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for <=: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for <=: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::BangEqual => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left != right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for !=: {:?} and {:?}", left, right),
-                    )),
-                },
-                Object::Str(left) => match right {
-                    Object::Str(right) => Ok(Object::from(left != right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for !=: {:?} and {:?}", left, right),
-                    )),
-                },
-                Object::Bool(left) => match right {
-                    Object::Bool(right) => Ok(Object::from(left != right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for !=: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for !=: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::EqualEqual => match left {
-                Object::Num(left) => match right {
-                    Object::Num(right) => Ok(Object::from(left == right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for ==: {} and {}", left, right),
-                    )),
-                },
-                Object::Str(left) => match right {
-                    Object::Str(right) => Ok(Object::from(left == right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for ==: {} and {}", left, right),
-                    )),
-                },
-                Object::Bool(left) => match right {
-                    Object::Bool(right) => Ok(Object::from(left == right)),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for ==: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for ==: {} and {}", left, right),
-                )),
-            },
-            TokenType::In => match left {
-                Object::Str(left) => match right {
-                    Object::Str(right) => Ok(Object::from(right.contains(&left))),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for in: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for in: {:?} and {:?}", left, right),
-                )),
-            },
-            TokenType::BangIn => match left {
-                Object::Str(left) => match right {
-                    Object::Str(right) => Ok(Object::from(!right.contains(&left))),
-                    _ => Err(LaxError::error(
-                        expr.operator.line,
-                        format!("Invalid operands for !in: {:?} and {:?}", left, right),
-                    )),
-                },
-                _ => Err(LaxError::error(
-                    expr.operator.line,
-                    format!("Invalid operands for !in: {:?} and {:?}", left, right),
-                )),
-            },
-            _ => Err(LaxError::error(
+        if let Some(result) = self.set_operation(&expr.operator.t_type, &left, &right) {
+            return Ok(result);
+        }
+
+        let invalid = || Err(self.invalid_operands(&expr.operator, &left, &right));
+        match (&expr.operator.t_type, &left, &right) {
+            (TokenType::Minus, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::Num(left - right))
+            }
+            (TokenType::Slash, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::Num(left / right))
+            }
+            (TokenType::Star, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::Num(left * right))
+            }
+            (TokenType::Plus, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::Num(left + right))
+            }
+            (TokenType::Plus, Object::Str(left), Object::Str(right)) => {
+                Ok(Object::Str(format!("{}{}", left, right)))
+            }
+            (TokenType::Greater, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::from(left > right))
+            }
+            (TokenType::GreaterEqual, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::from(left >= right))
+            }
+            (TokenType::Less, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::from(left < right))
+            }
+            (TokenType::LessEqual, Object::Num(left), Object::Num(right)) => {
+                Ok(Object::from(left <= right))
+            }
+            (
+                TokenType::EqualEqual | TokenType::BangEqual,
+                Object::Num(_) | Object::Str(_) | Object::Bool(_),
+                _,
+            ) if self.type_name(&left) != self.type_name(&right)
+                && !matches!(right, Object::Nil) =>
+            {
+                invalid()
+            }
+            (
+                TokenType::EqualEqual | TokenType::BangEqual,
+                Object::Num(_)
+                | Object::Str(_)
+                | Object::Bool(_)
+                | Object::Nil
+                | Object::Range(_)
+                | Object::Tuple(_)
+                | Object::Set(_),
+                _,
+            ) => {
+                let equal = self.is_equal(&left, &right);
+                Ok(Object::from(
+                    equal == expr.operator.is(TokenType::EqualEqual),
+                ))
+            }
+            (TokenType::DotDot | TokenType::DotDotEqual, Object::Num(start), Object::Num(end)) => {
+                Ok(Object::Range(Range {
+                    start: *start,
+                    end: *end,
+                    inclusive: expr.operator.is(TokenType::DotDotEqual),
+                }))
+            }
+            (TokenType::In, Object::Str(left), Object::Str(right)) => {
+                Ok(Object::from(right.contains(left.as_str())))
+            }
+            (TokenType::In, Object::Num(left), Object::Range(range)) => {
+                Ok(Object::from(range.contains(*left)))
+            }
+            (TokenType::BangIn, Object::Str(left), Object::Str(right)) => {
+                Ok(Object::from(!right.contains(left.as_str())))
+            }
+            (TokenType::BangIn, Object::Num(left), Object::Range(range)) => {
+                Ok(Object::from(!range.contains(*left)))
+            }
+            (
+                TokenType::Minus
+                | TokenType::Slash
+                | TokenType::Star
+                | TokenType::Plus
+                | TokenType::Greater
+                | TokenType::GreaterEqual
+                | TokenType::Less
+                | TokenType::LessEqual
+                | TokenType::EqualEqual
+                | TokenType::BangEqual
+                | TokenType::DotDot
+                | TokenType::DotDotEqual
+                | TokenType::In
+                | TokenType::BangIn
+                | TokenType::Pipe
+                | TokenType::Ampersand,
+                _,
+                _,
+            ) => invalid(),
+            _ => Err(LaxError::runtime(
+                ErrorKind::Type,
                 expr.operator.line,
                 format!("Invalid operator: {:?}", expr.operator.t_type),
             )),
@@ -252,7 +386,7 @@ impl ExprVisitor<Object> for Interpreter {
         &self,
         expr: &crate::expr::GroupingExpr,
     ) -> Result<Object, crate::error::LaxError> {
-        Ok(self.evaluate(&expr.expression)?)
+        self.evaluate(&expr.expression)
     }
 
     fn visit_literal_expr(
@@ -270,35 +404,217 @@ impl ExprVisitor<Object> for Interpreter {
 
         match expr.operator.t_type {
             TokenType::Minus => match right {
-                Object::Num(num) => return Ok(Object::Num(-num)),
-                _ => return Ok(Object::Nil),
+                Object::Num(num) => Ok(Object::Num(-num)),
+                _ => Ok(Object::Nil),
             },
-            TokenType::Bang | TokenType::Not => return Ok(Object::from(!self.is_truthy(&right))),
+            TokenType::Bang | TokenType::Not => Ok(Object::from(!self.is_truthy(&right))),
             TokenType::Plus => {
                 match right {
                     Object::Str(value) => {
                         // return Ok(Object::Num(value.parse().unwrap()))
                         if let Ok(num) = value.parse::<f64>() {
-                            return Ok(Object::Num(num));
+                            Ok(Object::Num(num))
                         } else {
-                            return Err(LaxError::error(
+                            Err(LaxError::runtime(
+                                ErrorKind::Value,
                                 expr.operator.line,
                                 format!("Could not parse {} to a number", value),
-                            ));
+                            ))
                         }
                     }
-                    _ => {
-                        return Err(LaxError::error(
-                            expr.operator.line,
-                            format!(
-                                "Invalid operands for +: {:?} and {:?}",
-                                right, expr.operator.t_type
-                            ),
-                        ))
-                    }
+                    _ => Err(LaxError::runtime(
+                        ErrorKind::Type,
+                        expr.operator.line,
+                        format!("Invalid operand for +: {}", right),
+                    )),
                 }
             }
-            _ => return Err(LaxError::error(0, "Unreachable".to_owned())),
-        };
+            _ => Err(LaxError::runtime(
+                ErrorKind::Type,
+                0,
+                "Unreachable".to_owned(),
+            )),
+        }
+    }
+
+    fn visit_variable_expr(
+        &self,
+        expr: &crate::expr::VariableExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        match self.environment.borrow().get(&expr.name.lexeme) {
+            Some(value) => Ok(value),
+            None => Err(LaxError::runtime(
+                ErrorKind::Name,
+                expr.name.line,
+                format!("Undefined variable '{}'.", expr.name.lexeme),
+            )),
+        }
+    }
+
+    fn visit_match_expr(
+        &self,
+        expr: &crate::expr::MatchExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let value = self.evaluate(&expr.value)?;
+
+        for arm in &expr.arms {
+            if !self.pattern_matches(&arm.pattern, &value) {
+                continue;
+            }
+
+            self.environment.borrow_mut().push_scope();
+            self.bind_pattern(&arm.pattern, &value);
+            let result = self.evaluate_arm(arm);
+            self.environment.borrow_mut().pop_scope();
+
+            if let Some(result) = result? {
+                return Ok(result);
+            }
+        }
+
+        Err(LaxError::runtime(
+            ErrorKind::Match,
+            expr.keyword.line,
+            format!("No match arm for value: {}", value),
+        ))
+    }
+
+    fn visit_get_expr(
+        &self,
+        expr: &crate::expr::GetExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let object = self.evaluate(&expr.object)?;
+
+        match &object {
+            Object::Error(err) => match expr.name.lexeme.as_str() {
+                "message" => Ok(Object::Str(err.message.clone())),
+                "line" => Ok(Object::Num(err.line as f64)),
+                "type" => Ok(Object::Str(err.kind.clone())),
+                _ => Err(LaxError::runtime(
+                    ErrorKind::Name,
+                    expr.name.line,
+                    format!("Undefined property '{}'.", expr.name.lexeme),
+                )),
+            },
+            _ => Err(LaxError::runtime(
+                ErrorKind::Type,
+                expr.name.line,
+                format!("Only errors have properties, got {}", object),
+            )),
+        }
+    }
+
+    fn visit_slice_expr(
+        &self,
+        expr: &crate::expr::SliceExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let object = self.evaluate(&expr.object)?;
+        let start = self.slice_bound(&expr.start, &expr.bracket)?;
+        let end = self.slice_bound(&expr.end, &expr.bracket)?;
+        let step = self.slice_bound(&expr.step, &expr.bracket)?.unwrap_or(1);
+        if step == 0 {
+            return Err(LaxError::runtime(
+                ErrorKind::Value,
+                expr.bracket.line,
+                "Slice step cannot be zero".to_owned(),
+            ));
+        }
+
+        match &object {
+            Object::Str(str) => {
+                let chars: Vec<char> = str.chars().collect();
+                let indices = self.slice_indices(chars.len(), start, end, step);
+                Ok(Object::Str(indices.iter().map(|&i| chars[i]).collect()))
+            }
+            Object::Tuple(elements) => {
+                let indices = self.slice_indices(elements.len(), start, end, step);
+                Ok(Object::Tuple(
+                    indices.iter().map(|&i| elements[i].clone()).collect(),
+                ))
+            }
+            _ => Err(LaxError::runtime(
+                ErrorKind::Type,
+                expr.bracket.line,
+                format!("Only strings and tuples can be sliced, got {}", object),
+            )),
+        }
+    }
+
+    fn visit_throw_expr(
+        &self,
+        expr: &crate::expr::ThrowExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let value = self.evaluate(&expr.value)?;
+        Err(LaxError::throw(expr.keyword.line, value))
+    }
+
+    fn visit_try_expr(
+        &self,
+        expr: &crate::expr::TryExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let mut result = self.evaluate(&expr.body);
+
+        if let (Err(err), Some(catch)) = (&result, &expr.catch) {
+            self.environment.borrow_mut().push_scope();
+            self.environment
+                .borrow_mut()
+                .define(&catch.name.lexeme, err.to_object());
+            result = self.evaluate(&catch.body);
+            self.environment.borrow_mut().pop_scope();
+        }
+
+        // An error raised by the finally block replaces the outcome of the try and catch blocks.
+        if let Some(finally) = &expr.finally {
+            self.evaluate(finally)?;
+        }
+
+        result
+    }
+
+    fn visit_tuple_expr(
+        &self,
+        expr: &crate::expr::TupleExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let mut elements = Vec::with_capacity(expr.elements.len());
+        for element in &expr.elements {
+            elements.push(self.evaluate(element)?);
+        }
+        Ok(Object::Tuple(elements))
+    }
+
+    fn visit_set_expr(
+        &self,
+        expr: &crate::expr::SetExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let mut elements = Vec::with_capacity(expr.elements.len());
+        for element in &expr.elements {
+            let element = self.evaluate(element)?;
+            if !self.set_contains(&elements, &element) {
+                elements.push(element);
+            }
+        }
+        Ok(Object::Set(elements))
+    }
+
+    fn visit_infix_expr(
+        &self,
+        expr: &crate::expr::InfixExpr,
+    ) -> Result<Object, crate::error::LaxError> {
+        let left = self.evaluate(&expr.left)?;
+        let right = self.evaluate(&expr.right)?;
+        let declaration = &expr.declaration;
+
+        // The body only sees the operator's own parameters.
+        let enclosing = self.environment.replace(Environment::new());
+        {
+            let mut environment = self.environment.borrow_mut();
+            environment.push_scope();
+            environment.define(&declaration.left.lexeme, left);
+            environment.define(&declaration.right.lexeme, right);
+        }
+        let result = self.evaluate(&declaration.body);
+        self.environment.replace(enclosing);
+
+        result
     }
 }
@@ -1,22 +1,40 @@
 use error::*;
 use token::*;
 
+pub mod checker;
+pub mod constant;
+pub mod environment;
 pub mod error;
 pub mod expr;
 pub mod interpreter;
 pub mod lox;
+pub mod macros;
 pub mod parser;
 pub mod printer;
 pub mod scanner;
 pub mod token;
+pub mod types;
 
 #[cfg(test)]
 pub mod tests {
     use crate::{
+        error::{ErrorKind, LaxError},
         expr::{BinaryExpr, Expr, GroupingExpr, LiteralExpr},
+        interpreter::Interpreter,
+        parser::Parser,
+        scanner::Scanner,
         token::{Object, Token, TokenType},
     };
 
+    fn interpret(source: &str) -> Result<Object, LaxError> {
+        let tokens = Scanner::new(source.to_string()).scan_tokens()?;
+        let mut parser = Parser::new(tokens);
+        let expr = parser.parse().expect("source should parse");
+        // Run the checker first, as the CLI does.
+        crate::checker::Checker::new().check(parser.operators.values(), &expr)?;
+        Interpreter::new().interpret(&expr)
+    }
+
     #[test]
     fn test_ast_printer() {
         use crate::expr::UnaryExpr;
@@ -54,4 +72,298 @@ pub mod tests {
         );
         println!("{}", printer.print(&expr).unwrap());
     }
+
+    #[test]
+    fn test_match_expr() {
+        let source = |value: &str| {
+            format!(
+                "match {value} {{ 0 => \"zero\", -5..0 => \"negative\", 1..=9 => \"digit\", \
+                 is String => \"string\", n if n > 100 => n, _ => \"other\" }}"
+            )
+        };
+
+        assert_eq!(interpret(&source("0")).unwrap().to_string(), "\"zero\"");
+        assert_eq!(
+            interpret(&source("-3")).unwrap().to_string(),
+            "\"negative\""
+        );
+        assert_eq!(interpret(&source("9")).unwrap().to_string(), "\"digit\"");
+        assert_eq!(interpret(&source("250")).unwrap().to_string(), "250");
+        assert_eq!(
+            interpret(&source("\"a\"")).unwrap().to_string(),
+            "\"string\""
+        );
+        assert_eq!(interpret(&source("50")).unwrap().to_string(), "\"other\"");
+
+        let source = "(match \"a\" { s if s => 1, _ => 2 }, match \"\" { s if s => 1, _ => 2 })";
+        assert_eq!(interpret(source).unwrap().to_string(), "(1, 2)");
+
+        let err = interpret("match true { false => 1 }").unwrap_err();
+        assert_eq!(err.message, "No match arm for value: true");
+    }
+
+    #[test]
+    fn test_try_catch() {
+        let caught = interpret("try { 1 + \"a\" } catch (e) { e.type + \": \" + e.message }");
+        assert_eq!(
+            caught.unwrap().to_string(),
+            "\"TypeError: Invalid operands for +: 1 and \"a\"\""
+        );
+
+        assert_eq!(
+            interpret("try { throw \"boom\" } catch (e) { e + \"!\" }")
+                .unwrap()
+                .to_string(),
+            "\"boom!\""
+        );
+
+        let err = interpret("try { throw 1 } finally { 2 }").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Thrown);
+        assert_eq!(err.value.unwrap().to_string(), "1");
+
+        let err = interpret("try { 1 } finally { throw 3 }").unwrap_err();
+        assert_eq!(err.value.unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(interpret("0..10").unwrap().to_string(), "0..10");
+        assert_eq!(interpret("1 + 1..=2 * 5").unwrap().to_string(), "2..=10");
+        assert_eq!(interpret("10 in 0..10").unwrap().to_string(), "false");
+        assert_eq!(interpret("10 in 0..=10").unwrap().to_string(), "true");
+        assert_eq!(interpret("-1 !in 0..10").unwrap().to_string(), "true");
+        assert!(interpret("\"a\"..10").is_err());
+        assert_eq!(interpret("0..10 == 0..10").unwrap().to_string(), "true");
+
+        let tokens = Scanner::new("1..2..3".to_string()).scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_none());
+    }
+
+    #[test]
+    fn test_tuple() {
+        assert_eq!(
+            interpret("(1, \"a\", (true,))").unwrap().to_string(),
+            "(1, \"a\", (true,))"
+        );
+        assert_eq!(interpret("(1)").unwrap().to_string(), "1");
+        assert_eq!(interpret("()").unwrap().to_string(), "()");
+        assert_eq!(
+            interpret("(1, (2, 3)) == (1, (2, 3))").unwrap().to_string(),
+            "true"
+        );
+        assert_eq!(
+            interpret("(1, 2) != (1, 2, 3)").unwrap().to_string(),
+            "true"
+        );
+
+        let source = "match (7, (1, 2)) { (q, (1, r)) => q + r, _ => 0 }";
+        assert_eq!(interpret(source).unwrap().to_string(), "9");
+
+        let source = "(match (1) { (x) => x }, match (1,) { (x,) => x, _ => 0 })";
+        assert_eq!(interpret(source).unwrap().to_string(), "(1, 1)");
+
+        let err = interpret("match (1, 2) { (a, b, c) => a }").unwrap_err();
+        assert_eq!(err.message, "No match arm for value: (1, 2)");
+    }
+
+    #[test]
+    fn test_set() {
+        let source = "({3, 1, 3, 2} | {4, 1}, {1, 2, 3} & {3, 2}, {1, 2, 3} - {2})";
+        assert_eq!(
+            interpret(source).unwrap().to_string(),
+            "({3, 1, 2, 4}, {2, 3}, {1, 3})"
+        );
+        let source = "(2 in {1, 2}, (1, 2) !in {(1, 2)}, {1} < {1, 2}, {2, 1} == {1, 2}, {})";
+        assert_eq!(
+            interpret(source).unwrap().to_string(),
+            "(true, false, true, true, {})"
+        );
+        assert_eq!(interpret("{1} | 2").unwrap_err().kind, ErrorKind::Type);
+    }
+
+    #[test]
+    fn test_slice() {
+        let source =
+            "(\"héllo\"[1:3], \"hello\"[-3:], \"hello\"[:-10], \"hello\"[::-2], \"hello\"[4:1:-1])";
+        assert_eq!(
+            interpret(source).unwrap().to_string(),
+            "(\"él\", \"llo\", \"\", \"olh\", \"oll\")"
+        );
+        assert_eq!(
+            interpret("(1, 2, 3, 4)[1:100:2]").unwrap().to_string(),
+            "(2, 4)"
+        );
+        assert_eq!(
+            interpret("\"abc\"[::0]").unwrap_err().kind,
+            ErrorKind::Value
+        );
+        let source = "(\"abc\"[1::10000000000000000000], (1, 2, 3)[1::10000000000000000000])";
+        assert_eq!(interpret(source).unwrap().to_string(), "(\"b\", (2,))");
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let source = r#"(r"C:\path\no\escapes", """
+            SELECT *
+              FROM t
+            """)"#;
+        assert_eq!(
+            interpret(source).unwrap().to_string(),
+            "(\"C:\\path\\no\\escapes\", \"SELECT *\n  FROM t\")"
+        );
+
+        // The error is on the line after the literal, not the line it starts on.
+        let source = "\"\"\"\n  a\n  b\n\"\"\"\n - 1";
+        assert_eq!(interpret(source).unwrap_err().line, 5);
+    }
+
+    #[test]
+    fn test_infix_operator() {
+        let source = "
+            infix 60 <+> (a, b) { (a + b) / 2 }
+            infix 75 ** (a, b) { a * a * b }
+            1 <+> 3 * 2 <+> 2 ** 3
+        ";
+        // ((1 <+> 6) <+> (2 ** 3)) = (3.5 <+> 12)
+        assert_eq!(interpret(source).unwrap().to_string(), "7.75");
+
+        assert_eq!(interpret("1==-1").unwrap().to_string(), "false");
+        assert_eq!(interpret("2*-3").unwrap().to_string(), "-6");
+        // Operators at the precedence of ranges still chain.
+        let source = "infix 50 <+> (a, b) { a + b } 1 <+> 2 <+> 3";
+        assert_eq!(interpret(source).unwrap().to_string(), "6");
+
+        let tokens = Scanner::new("1 <$> 2".to_string()).scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_none());
+    }
+
+    #[test]
+    fn test_macro() {
+        let source = "
+            macro shadow(v) { match 2 { x => v } }
+            match 1 { x => shadow!(x) }
+        ";
+        assert_eq!(interpret(source).unwrap().to_string(), "1");
+
+        let source = "
+            macro assert_eq(a, b) {
+                match (a, b) { (x, y) if x == y => x, _ => throw \"assertion failed\" }
+            }
+            assert_eq!(1 + 1, 2) + assert_eq!(3, 4)
+        ";
+        let err = interpret(source).unwrap_err();
+        assert_eq!(err.value.unwrap().to_string(), "\"assertion failed\"");
+        assert_eq!(err.line, 5);
+
+        for source in ["macro bad() { y } 1", "macro one(a) { a } one!(1, 2)"] {
+            let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+            assert!(Parser::new(tokens).parse().is_none());
+        }
+    }
+
+    #[test]
+    fn test_const() {
+        let source = "
+            const WIDTH = 2 + 1;
+            const AREA = WIDTH * WIDTH;
+            match 9 { AREA => (WIDTH, AREA), _ => nil }
+        ";
+        assert_eq!(interpret(source).unwrap().to_string(), "(3, 9)");
+
+        let tokens = Scanner::new("1 + match 1 { x => x }".to_string())
+            .scan_tokens()
+            .unwrap();
+        let expr = Parser::new(tokens).parse().unwrap();
+        let name = Token::new(TokenType::Identifier, "B".to_string(), None, 1);
+        let err = crate::constant::evaluate(&name, &expr).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Expression '(match 1 (x => x))' is not constant."
+        );
+    }
+
+    #[test]
+    fn test_checker() {
+        use crate::checker::Checker;
+        use crate::types::Type;
+
+        fn check(source: &str) -> Result<Type, LaxError> {
+            let tokens = Scanner::new(source.to_string()).scan_tokens()?;
+            let mut parser = Parser::new(tokens);
+            let expr = parser.parse().expect("source should parse");
+            Checker::new().check(parser.operators.values(), &expr)
+        }
+
+        let source = "
+            infix 60 <?> (a: Option<Number>, b: Number): Number {
+                match a { nil => b, n => n + b }
+            }
+            nil <?> 1 <?> 2
+        ";
+        assert_eq!(check(source).unwrap(), Type::Number);
+        for arms in ["nil => b, _ => a + b", "is Number => a + b, _ => b"] {
+            let source = format!(
+                "infix 60 <?> (a: Option<Number>, b: Number): Number {{ match a {{ {arms} }} }} 1 <?> 2"
+            );
+            assert_eq!(check(&source).unwrap(), Type::Number);
+        }
+        for condition in ["a == 1", "1 == a"] {
+            let source = format!(
+                "infix 60 <?> (a: Option<Number>, b: Number): Bool {{ {condition} }} 1 <?> 2"
+            );
+            assert_eq!(check(&source).unwrap(), Type::Bool);
+        }
+        let source =
+            "infix 60 <?> (a: Option<Number>, b: Number): Bool { a == b } (nil <?> 2, 2 <?> 2)";
+        assert_eq!(interpret(source).unwrap().to_string(), "(false, true)");
+        assert!(check("(1, 2) == 3").is_ok());
+        assert!(check("1 == \"a\"").is_err());
+        assert_eq!(
+            check("match 1 { 1 => nil, _ => 2 }").unwrap().to_string(),
+            "Option<Number>"
+        );
+
+        let err = check("1 + \"a\"").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Type);
+        assert_eq!(
+            err.message,
+            "Expected Number operand for '+', found String."
+        );
+
+        let source = "
+            infix 60 <?> (a: Option<Number>, b: Number): Number { a + b }
+            1
+        ";
+        assert!(check(source).unwrap_err().message.contains("may be nil"));
+        assert!(check("infix 60 <!> (a: Number, b: Number): String { a } 1").is_err());
+        for source in [
+            "(throw 1) + 2",
+            "(throw 1).foo",
+            "(throw 1)[0:1]",
+            "1 in (throw 1)",
+        ] {
+            assert!(check(source).is_ok());
+        }
+        assert!(check("try { 1 + \"a\" } catch (e) { 0 }").is_ok());
+        let source = "
+            infix 60 <?> (a: Number, b: Number): Number { b }
+            try { \"x\" <?> 1 } catch (e) { 0 }
+        ";
+        assert!(check(source).is_err());
+        let declaration =
+            "infix 60 <?> <T>(a: Option<T>, b: T): T { match a { nil => b, _ => a } }";
+        let source = format!("{declaration} nil <?> \"a\"");
+        assert_eq!(check(&source).unwrap(), Type::String);
+        let source = format!("{declaration} (nil <?> 2, 1 <?> 2)");
+        assert_eq!(interpret(&source).unwrap().to_string(), "(2, 1)");
+        let source = format!("{declaration} 1 <?> \"a\"");
+        assert_eq!(
+            check(&source).unwrap_err().message,
+            "Expected Number operand for '<?>', found String."
+        );
+        let source = "infix 60 <+> <T>(a: T, b: T): T { a + b } 1";
+        assert!(check(source).is_err());
+        // Tuple elements are not tracked, so the mistake is left to the interpreter.
+        assert!(check("match (1, \"a\") { (a, b) => a + b }").is_ok());
+    }
 }
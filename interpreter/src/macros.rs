@@ -0,0 +1,232 @@
+//! This module contains the expansion of `macro` declarations
+//!
+//! A macro body is a quoted expression. Expanding an invocation copies that
+//! expression, replaces the parameters with the argument expressions and gives
+//! every name the body binds itself a fresh name, so a macro can neither capture
+//! nor shadow the names at the invocation site.
+
+use std::collections::HashMap;
+
+use crate::{
+    error::LaxError,
+    expr::{
+        BinaryExpr, CatchClause, Expr, GetExpr, GroupingExpr, InfixExpr, LiteralExpr, MatchArm,
+        MatchExpr, Pattern, SetExpr, SliceExpr, ThrowExpr, TryExpr, TupleExpr, UnaryExpr,
+        VariableExpr,
+    },
+    token::Token,
+};
+
+/// `macro name(params) { body }`
+pub struct MacroDecl {
+    pub name: Token,
+    pub params: Vec<Token>,
+    pub body: Expr,
+}
+
+/// What a name in the macro body stands for.
+#[derive(Clone)]
+enum Name<'a> {
+    /// A parameter, replaced by the argument expression.
+    Argument(&'a Expr),
+    /// A name bound inside the macro body, renamed to a fresh name.
+    Renamed(String),
+}
+
+pub struct Expander<'a> {
+    /// The token errors are reported at.
+    site: &'a Token,
+    /// Counter for fresh names, shared by all expansions of one parser.
+    expansions: &'a mut usize,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(site: &'a Token, expansions: &'a mut usize) -> Self {
+        Self { site, expansions }
+    }
+
+    /// Expands `declaration` with `arguments`.
+    ///
+    /// Tokens copied from the macro body take the line of `site`, so runtime errors
+    /// in the expansion point at the invocation.
+    pub fn expand(
+        &mut self,
+        declaration: &MacroDecl,
+        arguments: &'a [Expr],
+    ) -> Result<Expr, LaxError> {
+        if arguments.len() != declaration.params.len() {
+            return Err(LaxError::parse_error(
+                self.site.clone(),
+                &format!(
+                    "Expected {} arguments but got {}.",
+                    declaration.params.len(),
+                    arguments.len()
+                ),
+            ));
+        }
+
+        let scope = declaration
+            .params
+            .iter()
+            .zip(arguments.iter())
+            .map(|(param, argument)| (param.lexeme.clone(), Name::Argument(argument)))
+            .collect();
+        self.expr(&declaration.body, &scope)
+    }
+
+    fn expr(&mut self, expr: &Expr, scope: &HashMap<String, Name<'a>>) -> Result<Expr, LaxError> {
+        Ok(match expr {
+            Expr::Binary(expr) => Expr::Binary(BinaryExpr {
+                left: Box::new(self.expr(&expr.left, scope)?),
+                operator: self.token(&expr.operator),
+                right: Box::new(self.expr(&expr.right, scope)?),
+            }),
+            Expr::Grouping(expr) => Expr::Grouping(GroupingExpr {
+                expression: Box::new(self.expr(&expr.expression, scope)?),
+            }),
+            Expr::Literal(expr) => Expr::Literal(LiteralExpr {
+                value: expr.value.clone(),
+            }),
+            Expr::Unary(expr) => Expr::Unary(UnaryExpr {
+                operator: self.token(&expr.operator),
+                right: Box::new(self.expr(&expr.right, scope)?),
+            }),
+            Expr::Variable(expr) => match scope.get(&expr.name.lexeme) {
+                Some(Name::Argument(argument)) => (*argument).clone(),
+                Some(Name::Renamed(name)) => Expr::Variable(VariableExpr {
+                    name: self.renamed(&expr.name, name),
+                }),
+                None => {
+                    return Err(LaxError::parse_error(
+                        self.site.clone(),
+                        &format!("Undefined name '{}' in macro body.", expr.name.lexeme),
+                    ))
+                }
+            },
+            Expr::Match(expr) => {
+                let mut arms = Vec::with_capacity(expr.arms.len());
+                for arm in &expr.arms {
+                    let mut scope = scope.clone();
+                    let pattern = self.pattern(&arm.pattern, &mut scope);
+                    let guard = match &arm.guard {
+                        Some(guard) => Some(self.expr(guard, &scope)?),
+                        None => None,
+                    };
+                    arms.push(MatchArm {
+                        pattern,
+                        guard,
+                        body: self.expr(&arm.body, &scope)?,
+                    });
+                }
+                Expr::Match(MatchExpr {
+                    keyword: self.token(&expr.keyword),
+                    value: Box::new(self.expr(&expr.value, scope)?),
+                    arms,
+                })
+            }
+            Expr::Get(expr) => Expr::Get(GetExpr {
+                object: Box::new(self.expr(&expr.object, scope)?),
+                name: self.token(&expr.name),
+            }),
+            Expr::Slice(expr) => Expr::Slice(SliceExpr {
+                object: Box::new(self.expr(&expr.object, scope)?),
+                bracket: self.token(&expr.bracket),
+                start: self.bound(&expr.start, scope)?,
+                end: self.bound(&expr.end, scope)?,
+                step: self.bound(&expr.step, scope)?,
+            }),
+            Expr::Throw(expr) => Expr::Throw(ThrowExpr {
+                keyword: self.token(&expr.keyword),
+                value: Box::new(self.expr(&expr.value, scope)?),
+            }),
+            Expr::Try(expr) => {
+                let catch = match &expr.catch {
+                    Some(catch) => {
+                        let mut scope = scope.clone();
+                        let name = self.bind(&catch.name, &mut scope);
+                        Some(CatchClause {
+                            name,
+                            body: Box::new(self.expr(&catch.body, &scope)?),
+                        })
+                    }
+                    None => None,
+                };
+                let finally = match &expr.finally {
+                    Some(finally) => Some(Box::new(self.expr(finally, scope)?)),
+                    None => None,
+                };
+                Expr::Try(TryExpr {
+                    body: Box::new(self.expr(&expr.body, scope)?),
+                    catch,
+                    finally,
+                })
+            }
+            Expr::Tuple(expr) => {
+                let mut elements = Vec::with_capacity(expr.elements.len());
+                for element in &expr.elements {
+                    elements.push(self.expr(element, scope)?);
+                }
+                Expr::Tuple(TupleExpr { elements })
+            }
+            Expr::Set(expr) => {
+                let mut elements = Vec::with_capacity(expr.elements.len());
+                for element in &expr.elements {
+                    elements.push(self.expr(element, scope)?);
+                }
+                Expr::Set(SetExpr { elements })
+            }
+            Expr::Infix(expr) => Expr::Infix(InfixExpr {
+                left: Box::new(self.expr(&expr.left, scope)?),
+                operator: self.token(&expr.operator),
+                right: Box::new(self.expr(&expr.right, scope)?),
+                declaration: expr.declaration.clone(),
+            }),
+        })
+    }
+
+    fn bound(
+        &mut self,
+        bound: &Option<Box<Expr>>,
+        scope: &HashMap<String, Name<'a>>,
+    ) -> Result<Option<Box<Expr>>, LaxError> {
+        Ok(match bound {
+            Some(bound) => Some(Box::new(self.expr(bound, scope)?)),
+            None => None,
+        })
+    }
+
+    fn pattern(&mut self, pattern: &Pattern, scope: &mut HashMap<String, Name<'a>>) -> Pattern {
+        match pattern {
+            Pattern::Binding(name) => Pattern::Binding(self.bind(name, scope)),
+            Pattern::Type(name) => Pattern::Type(self.token(name)),
+            Pattern::Tuple(patterns) => Pattern::Tuple(
+                patterns
+                    .iter()
+                    .map(|pattern| self.pattern(pattern, scope))
+                    .collect(),
+            ),
+            _ => pattern.clone(),
+        }
+    }
+
+    /// Gives a name bound by the macro body a fresh name and records it in `scope`.
+    fn bind(&mut self, name: &Token, scope: &mut HashMap<String, Name<'a>>) -> Token {
+        *self.expansions += 1;
+        // '#' can't appear in an identifier, so the fresh name can't clash with user names.
+        let fresh = format!("{}#{}", name.lexeme, self.expansions);
+        scope.insert(name.lexeme.clone(), Name::Renamed(fresh.clone()));
+        self.renamed(name, &fresh)
+    }
+
+    fn renamed(&self, name: &Token, fresh: &str) -> Token {
+        let mut token = self.token(name);
+        token.lexeme = fresh.to_owned();
+        token
+    }
+
+    fn token(&self, token: &Token) -> Token {
+        let mut token = token.clone();
+        token.line = self.site.line;
+        token
+    }
+}
@@ -1,98 +1,335 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use crate::{
+    constant,
     error::LaxError,
-    expr::{BinaryExpr, Expr, GroupingExpr, LiteralExpr, UnaryExpr},
-    token::{Object, Token, TokenType},
+    expr::{
+        BinaryExpr, CatchClause, Expr, GetExpr, GroupingExpr, InfixDecl, InfixExpr, LiteralExpr,
+        MatchArm, MatchExpr, Pattern, SetExpr, SliceExpr, ThrowExpr, TryExpr, TupleExpr, UnaryExpr,
+        VariableExpr,
+    },
+    macros::{Expander, MacroDecl},
+    token::{Object, Range, Token, TokenType},
+    types::Type,
 };
 
+// Precedences of the built-in binary operators.
+const EQUALITY: u8 = 30;
+const COMPARISON: u8 = 40;
+const UNION: u8 = 44;
+const INTERSECTION: u8 = 46;
+const RANGE: u8 = 50;
+const TERM: u8 = 60;
+const FACTOR: u8 = 70;
+
 pub struct Parser {
     pub tokens: Vec<Token>,
     pub current: usize,
+    /// Operators declared with `infix`, by symbol.
+    pub operators: HashMap<String, Rc<InfixDecl>>,
+    /// Macros declared with `macro`, by name.
+    pub macros: HashMap<String, MacroDecl>,
+    /// Number of fresh names handed out by macro expansion.
+    pub expansions: usize,
+    /// Values of the constants declared with `const`, by name.
+    pub constants: HashMap<String, Object>,
+    /// Type parameters of the infix declaration being parsed.
+    pub type_params: Vec<Token>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            operators: HashMap::new(),
+            macros: HashMap::new(),
+            expansions: 0,
+            constants: HashMap::new(),
+            type_params: Vec::new(),
+        }
     }
 
     pub fn parse(&mut self) -> Option<Expr> {
-        match self.expression() {
-            Ok(expr) => Some(expr),
-            Err(_) => None,
+        self.program().ok()
+    }
+
+    /// A program is any number of operator, macro and constant declarations followed by
+    /// an expression.
+    fn program(&mut self) -> Result<Expr, LaxError> {
+        loop {
+            if self.match_token(&[TokenType::Infix]) {
+                self.infix_declaration()?;
+            } else if self.match_token(&[TokenType::Macro]) {
+                self.macro_declaration()?;
+            } else if self.match_token(&[TokenType::Const]) {
+                self.const_declaration()?;
+            } else {
+                return self.expression();
+            }
         }
     }
 
     fn expression(&mut self) -> Result<Expr, LaxError> {
-        self.equality()
+        self.binary(0)
     }
 
-    fn equality(&mut self) -> Result<Expr, LaxError> {
-        let mut expr = self.comparison()?;
+    /// Parses binary operators by precedence climbing over [`Parser::precedence`].
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr, LaxError> {
+        let mut expr = self.unary()?;
 
-        while self.match_token(&[TokenType::Equal, TokenType::BangEqual, TokenType::BangIn]) {
-            let operator = self.previous();
-            let right = self.comparison()?;
-            expr = Expr::Binary(BinaryExpr {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            });
+        while let Some(precedence) = self.precedence(&self.peek())? {
+            if precedence < min_precedence {
+                break;
+            }
+            let operator = self.advance();
+            let right = self.binary(precedence + 1)?;
+
+            // Ranges don't chain: `a..b..c` is not an expression.
+            let is_range = operator.is(TokenType::DotDot) || operator.is(TokenType::DotDotEqual);
+            if is_range && (self.check(TokenType::DotDot) || self.check(TokenType::DotDotEqual)) {
+                return Err(LaxError::parse_error(
+                    self.peek(),
+                    "Ranges can't be chained.",
+                ));
+            }
+
+            expr = match self.operators.get(&operator.lexeme) {
+                Some(declaration) if operator.is(TokenType::Operator) => Expr::Infix(InfixExpr {
+                    left: Box::new(expr),
+                    operator,
+                    right: Box::new(right),
+                    declaration: Rc::clone(declaration),
+                }),
+                _ => Expr::Binary(BinaryExpr {
+                    left: Box::new(expr),
+                    operator,
+                    right: Box::new(right),
+                }),
+            };
         }
 
         Ok(expr)
     }
 
-    fn comparison(&mut self) -> Result<Expr, LaxError> {
-        let mut expr = self.term()?;
+    /// Returns the precedence of the binary operator `token`, or `None` if it is not one.
+    ///
+    /// Higher precedences bind tighter. Operators declared with `infix` share this scale.
+    fn precedence(&self, token: &Token) -> Result<Option<u8>, LaxError> {
+        Ok(match token.t_type {
+            TokenType::Equal | TokenType::BangEqual | TokenType::BangIn => Some(EQUALITY),
+            TokenType::Greater
+            | TokenType::GreaterEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+            | TokenType::EqualEqual
+            | TokenType::In => Some(COMPARISON),
+            TokenType::Pipe => Some(UNION),
+            TokenType::Ampersand => Some(INTERSECTION),
+            TokenType::DotDot | TokenType::DotDotEqual => Some(RANGE),
+            TokenType::Minus | TokenType::Plus => Some(TERM),
+            TokenType::Star | TokenType::Slash => Some(FACTOR),
+            TokenType::Operator => match self.operators.get(&token.lexeme) {
+                Some(declaration) => Some(declaration.precedence),
+                None => return Err(LaxError::parse_error(token.clone(), "Unknown operator.")),
+            },
+            _ => None,
+        })
+    }
 
-        while self.match_token(&[
-            TokenType::Greater,
-            TokenType::GreaterEqual,
-            TokenType::Less,
-            TokenType::LessEqual,
-            TokenType::EqualEqual,
-            TokenType::In,
-        ]) {
-            let operator = self.previous();
-            let right = self.term()?;
-            expr = Expr::Binary(BinaryExpr {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            });
+    fn infix_declaration(&mut self) -> Result<(), LaxError> {
+        let token = self.consume(TokenType::Number, "Expect precedence after 'infix'.")?;
+        let precedence = match token.literal {
+            Some(Object::Num(num)) if num.fract() == 0.0 && (0.0..=100.0).contains(&num) => {
+                num as u8
+            }
+            _ => {
+                return Err(LaxError::parse_error(
+                    token,
+                    "Precedence must be a whole number from 0 to 100.",
+                ))
+            }
+        };
+
+        let symbol = self.consume(TokenType::Operator, "Expect new operator symbol.")?;
+        if self.operators.contains_key(&symbol.lexeme) {
+            return Err(LaxError::parse_error(
+                symbol,
+                "Operator is already declared.",
+            ));
         }
-        Ok(expr)
+
+        self.type_params.clear();
+        if self.match_token(&[TokenType::Less]) {
+            loop {
+                let name = self.consume(TokenType::Identifier, "Expect type parameter name.")?;
+                if Type::named(&name.lexeme).is_some()
+                    || name.lexeme == "Option"
+                    || self
+                        .type_params
+                        .iter()
+                        .any(|param| param.lexeme == name.lexeme)
+                {
+                    return Err(LaxError::parse_error(
+                        name,
+                        "Type parameter name is already used.",
+                    ));
+                }
+                self.type_params.push(name);
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::Greater, "Expect '>' after type parameters.")?;
+        }
+
+        self.consume(TokenType::LeftParen, "Expect '(' after operator symbol.")?;
+        let left = self.binding_name("Expect parameter name.")?;
+        let left_type = self.annotation()?;
+        self.consume(TokenType::Comma, "Expect ',' between operator parameters.")?;
+        let right = self.binding_name("Expect parameter name.")?;
+        let right_type = self.annotation()?;
+        self.consume(
+            TokenType::RightParent,
+            "Expect ')' after operator parameters.",
+        )?;
+        let return_type = self.annotation()?;
+        let type_params = std::mem::take(&mut self.type_params);
+        let body = self.block("operator")?;
+
+        self.operators.insert(
+            symbol.lexeme.clone(),
+            Rc::new(InfixDecl {
+                symbol,
+                precedence,
+                type_params,
+                left,
+                left_type,
+                right,
+                right_type,
+                return_type,
+                body,
+            }),
+        );
+        Ok(())
     }
 
-    fn term(&mut self) -> Result<Expr, LaxError> {
-        let mut expr = self.factor()?;
+    fn const_declaration(&mut self) -> Result<(), LaxError> {
+        let name = self.consume(TokenType::Identifier, "Expect constant name.")?;
+        if self.constants.contains_key(&name.lexeme) {
+            return Err(LaxError::parse_error(name, "Constant is already declared."));
+        }
+        let annotation = self.annotation()?;
+        self.consume(TokenType::Equal, "Expect '=' after constant name.")?;
+        let value = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after constant value.")?;
 
-        while self.match_token(&[TokenType::Minus, TokenType::Plus]) {
-            let operator = self.previous();
-            let right = self.factor()?;
-            expr = Expr::Binary(BinaryExpr {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            });
+        let value = constant::evaluate(&name, &value)?;
+        let found = Type::of(&value);
+        if !annotation.accepts(&found) {
+            return Err(LaxError::type_error(
+                name,
+                &format!("Expected {}, found {}.", annotation, found),
+            ));
         }
+        self.constants.insert(name.lexeme, value);
+        Ok(())
+    }
 
-        Ok(expr)
+    /// Parses an optional `: Type` annotation. A missing annotation means [`Type::Any`].
+    fn annotation(&mut self) -> Result<Type, LaxError> {
+        if self.match_token(&[TokenType::Colon]) {
+            self.type_expr()
+        } else {
+            Ok(Type::Any)
+        }
     }
 
-    fn factor(&mut self) -> Result<Expr, LaxError> {
-        let mut expr = self.unary()?;
+    fn type_expr(&mut self) -> Result<Type, LaxError> {
+        let name = self.consume(TokenType::Identifier, "Expect type name.")?;
+        if name.lexeme == "Option" {
+            self.consume(TokenType::Less, "Expect '<' after 'Option'.")?;
+            let inner = self.type_expr()?;
+            self.consume(TokenType::Greater, "Expect '>' after option type.")?;
+            return Ok(Type::Option(Box::new(inner)));
+        }
+        if self
+            .type_params
+            .iter()
+            .any(|param| param.lexeme == name.lexeme)
+        {
+            return Ok(Type::Param(name.lexeme));
+        }
+        match Type::named(&name.lexeme) {
+            Some(t_type) => Ok(t_type),
+            None => Err(LaxError::parse_error(name, "Unknown type.")),
+        }
+    }
 
-        while self.match_token(&[TokenType::Star, TokenType::Slash]) {
-            let operator = self.previous();
-            let right = self.unary()?;
-            expr = Expr::Binary(BinaryExpr {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            });
+    /// Consumes a name that is about to be bound, which must not be a constant.
+    fn binding_name(&mut self, message: &str) -> Result<Token, LaxError> {
+        let name = self.consume(TokenType::Identifier, message)?;
+        if self.constants.contains_key(&name.lexeme) {
+            return Err(LaxError::parse_error(name, "Can't rebind a constant."));
         }
+        Ok(name)
+    }
 
-        Ok(expr)
+    fn macro_declaration(&mut self) -> Result<(), LaxError> {
+        let name = self.consume(TokenType::Identifier, "Expect macro name.")?;
+        if self.macros.contains_key(&name.lexeme) {
+            return Err(LaxError::parse_error(name, "Macro is already declared."));
+        }
+
+        self.consume(TokenType::LeftParen, "Expect '(' after macro name.")?;
+        let mut params = Vec::new();
+        while !self.check(TokenType::RightParent) {
+            params.push(self.binding_name("Expect parameter name.")?);
+            if !self.match_token(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParent, "Expect ')' after macro parameters.")?;
+        let body = self.block("macro")?;
+
+        let declaration = MacroDecl { name, params, body };
+        // Expanding with the parameters themselves reports names the body can't resolve.
+        let params: Vec<Expr> = declaration
+            .params
+            .iter()
+            .map(|param| {
+                Expr::Variable(VariableExpr {
+                    name: param.clone(),
+                })
+            })
+            .collect();
+        Expander::new(&declaration.name, &mut self.expansions).expand(&declaration, &params)?;
+
+        self.macros
+            .insert(declaration.name.lexeme.clone(), declaration);
+        Ok(())
+    }
+
+    /// Parses the arguments of `name!(...)` and expands the macro in place.
+    fn macro_invocation(&mut self, name: Token) -> Result<Expr, LaxError> {
+        self.consume(TokenType::Bang, "Expect '!' after macro name.")?;
+        self.consume(TokenType::LeftParen, "Expect '(' after '!'.")?;
+        let mut arguments = Vec::new();
+        while !self.check(TokenType::RightParent) {
+            arguments.push(self.expression()?);
+            if !self.match_token(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParent, "Expect ')' after macro arguments.")?;
+
+        match self.macros.get(&name.lexeme) {
+            Some(declaration) => {
+                Expander::new(&name, &mut self.expansions).expand(declaration, &arguments)
+            }
+            None => Err(LaxError::parse_error(name, "Unknown macro.")),
+        }
     }
 
     fn unary(&mut self) -> Result<Expr, LaxError> {
@@ -109,7 +346,63 @@ impl Parser {
                 right: Box::new(right),
             }));
         }
-        self.primary()
+        if self.match_token(&[TokenType::Throw]) {
+            let keyword = self.previous();
+            let value = self.expression()?;
+            return Ok(Expr::Throw(ThrowExpr {
+                keyword,
+                value: Box::new(value),
+            }));
+        }
+        self.property()
+    }
+
+    fn property(&mut self) -> Result<Expr, LaxError> {
+        let mut expr = self.primary()?;
+
+        while self.match_token(&[TokenType::Dot, TokenType::LeftBracket]) {
+            if self.previous().is(TokenType::LeftBracket) {
+                expr = self.slice(expr)?;
+                continue;
+            }
+            let name = self.consume(TokenType::Identifier, "Expect property name after '.'.")?;
+            expr = Expr::Get(GetExpr {
+                object: Box::new(expr),
+                name,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses the rest of `object[start:end:step]` after its `[`.
+    fn slice(&mut self, object: Expr) -> Result<Expr, LaxError> {
+        let bracket = self.previous();
+        let start = self.slice_bound()?;
+        self.consume(TokenType::Colon, "Expect ':' in slice.")?;
+        let end = self.slice_bound()?;
+        let step = if self.match_token(&[TokenType::Colon]) {
+            self.slice_bound()?
+        } else {
+            None
+        };
+        self.consume(TokenType::RightBracket, "Expect ']' after slice.")?;
+
+        Ok(Expr::Slice(SliceExpr {
+            object: Box::new(object),
+            bracket,
+            start,
+            end,
+            step,
+        }))
+    }
+
+    fn slice_bound(&mut self) -> Result<Option<Box<Expr>>, LaxError> {
+        if self.check(TokenType::Colon) || self.check(TokenType::RightBracket) {
+            Ok(None)
+        } else {
+            Ok(Some(Box::new(self.expression()?)))
+        }
     }
 
     fn primary(&mut self) -> Result<Expr, LaxError> {
@@ -129,8 +422,31 @@ impl Parser {
             Ok(Expr::Literal(LiteralExpr {
                 value: self.previous().literal,
             }))
+        } else if self.match_token(&[TokenType::Identifier]) {
+            let name = self.previous();
+            if self.check(TokenType::Bang) && self.check_next(TokenType::LeftParen) {
+                return self.macro_invocation(name);
+            }
+            if let Some(value) = self.constants.get(&name.lexeme) {
+                return Ok(Expr::Literal(LiteralExpr {
+                    value: Some(value.clone()),
+                }));
+            }
+            Ok(Expr::Variable(VariableExpr { name }))
+        } else if self.match_token(&[TokenType::Match]) {
+            self.match_expr()
+        } else if self.match_token(&[TokenType::Try]) {
+            self.try_expr()
+        } else if self.match_token(&[TokenType::LeftBrace]) {
+            self.set()
         } else if self.match_token(&[TokenType::LeftParen]) {
+            if self.match_token(&[TokenType::RightParent]) {
+                return Ok(Expr::Tuple(TupleExpr { elements: vec![] }));
+            }
             let expr = self.expression()?;
+            if self.match_token(&[TokenType::Comma]) {
+                return self.tuple(expr);
+            }
             self.consume(TokenType::RightParent, "Expect ')' after expression.")?;
             Ok(Expr::Grouping(GroupingExpr {
                 expression: Box::new(expr),
@@ -143,6 +459,207 @@ impl Parser {
         }
     }
 
+    /// Parses the rest of a tuple after its first element and comma.
+    fn tuple(&mut self, first: Expr) -> Result<Expr, LaxError> {
+        let mut elements = vec![first];
+        while !self.check(TokenType::RightParent) {
+            elements.push(self.expression()?);
+            if !self.match_token(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParent, "Expect ')' after tuple elements.")?;
+        Ok(Expr::Tuple(TupleExpr { elements }))
+    }
+
+    /// Parses the rest of a set literal after its `{`.
+    fn set(&mut self) -> Result<Expr, LaxError> {
+        let mut elements = Vec::new();
+        while !self.check(TokenType::RightBrace) {
+            elements.push(self.expression()?);
+            if !self.match_token(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after set elements.")?;
+        Ok(Expr::Set(SetExpr { elements }))
+    }
+
+    fn match_expr(&mut self) -> Result<Expr, LaxError> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+        self.consume(TokenType::LeftBrace, "Expect '{' after match value.")?;
+
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let pattern = self.pattern()?;
+            let guard = if self.match_token(&[TokenType::If]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            self.consume(TokenType::FatArrow, "Expect '=>' after match pattern.")?;
+            let body = self.expression()?;
+            arms.push(MatchArm {
+                pattern,
+                guard,
+                body,
+            });
+
+            if !self.match_token(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expect '}' after match arms.")?;
+
+        Ok(Expr::Match(MatchExpr {
+            keyword,
+            value: Box::new(value),
+            arms,
+        }))
+    }
+
+    fn try_expr(&mut self) -> Result<Expr, LaxError> {
+        let keyword = self.previous();
+        let body = self.block("try")?;
+
+        let catch = if self.match_token(&[TokenType::Catch]) {
+            self.consume(TokenType::LeftParen, "Expect '(' after 'catch'.")?;
+            let name = self.binding_name("Expect error name.")?;
+            self.consume(TokenType::RightParent, "Expect ')' after error name.")?;
+            Some(CatchClause {
+                name,
+                body: Box::new(self.block("catch")?),
+            })
+        } else {
+            None
+        };
+
+        let finally = if self.match_token(&[TokenType::Finally]) {
+            Some(Box::new(self.block("finally")?))
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return Err(LaxError::parse_error(
+                keyword,
+                "Expect 'catch' or 'finally' after try block.",
+            ));
+        }
+
+        Ok(Expr::Try(TryExpr {
+            body: Box::new(body),
+            catch,
+            finally,
+        }))
+    }
+
+    /// Parses `{ expression }`.
+    fn block(&mut self, name: &str) -> Result<Expr, LaxError> {
+        self.consume(
+            TokenType::LeftBrace,
+            &format!("Expect '{{' before {} block.", name),
+        )?;
+        let expr = self.expression()?;
+        self.consume(
+            TokenType::RightBrace,
+            &format!("Expect '}}' after {} block.", name),
+        )?;
+        Ok(expr)
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, LaxError> {
+        if self.match_token(&[TokenType::Is]) {
+            let name = self.consume(TokenType::Identifier, "Expect type name after 'is'.")?;
+            if !matches!(
+                name.lexeme.as_str(),
+                "Number" | "String" | "Bool" | "Nil" | "Error" | "Range" | "Tuple" | "Set"
+            ) {
+                return Err(LaxError::parse_error(name, "Unknown type in pattern."));
+            }
+            return Ok(Pattern::Type(name));
+        }
+        if self.match_token(&[TokenType::LeftParen]) {
+            if self.match_token(&[TokenType::RightParent]) {
+                return Ok(Pattern::Tuple(vec![]));
+            }
+            // Like expressions, `(p)` is a grouping and `(p,)` a tuple.
+            let first = self.pattern()?;
+            if !self.match_token(&[TokenType::Comma]) {
+                self.consume(TokenType::RightParent, "Expect ')' after pattern.")?;
+                return Ok(first);
+            }
+            let mut elements = vec![first];
+            while !self.check(TokenType::RightParent) {
+                elements.push(self.pattern()?);
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightParent, "Expect ')' after tuple pattern.")?;
+            return Ok(Pattern::Tuple(elements));
+        }
+        if self.match_token(&[TokenType::Identifier]) {
+            let name = self.previous();
+            if name.lexeme == "_" {
+                return Ok(Pattern::Wildcard);
+            }
+            // A constant in a pattern matches its value, like a literal.
+            if let Some(value) = self.constants.get(&name.lexeme) {
+                return Ok(Pattern::Literal(value.clone()));
+            }
+            return Ok(Pattern::Binding(name));
+        }
+
+        let value = self.pattern_literal()?;
+        if let Object::Num(start) = value {
+            if self.match_token(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+                let inclusive = self.previous().is(TokenType::DotDotEqual);
+                match self.pattern_literal()? {
+                    Object::Num(end) => {
+                        return Ok(Pattern::Range(Range {
+                            start,
+                            end,
+                            inclusive,
+                        }))
+                    }
+                    _ => {
+                        return Err(LaxError::parse_error(
+                            self.previous(),
+                            "Expect number as range end.",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(Pattern::Literal(value))
+    }
+
+    fn pattern_literal(&mut self) -> Result<Object, LaxError> {
+        if self.match_token(&[TokenType::True]) {
+            Ok(Object::Bool(true))
+        } else if self.match_token(&[TokenType::False]) {
+            Ok(Object::Bool(false))
+        } else if self.match_token(&[TokenType::Nil]) {
+            Ok(Object::Nil)
+        } else if self.match_token(&[TokenType::String]) {
+            Ok(self.previous().literal.unwrap_or(Object::Nil))
+        } else if self.match_token(&[TokenType::Minus]) {
+            match self
+                .consume(TokenType::Number, "Expect number after '-'.")?
+                .literal
+            {
+                Some(Object::Num(num)) => Ok(Object::Num(-num)),
+                _ => Ok(Object::Nil),
+            }
+        } else if self.match_token(&[TokenType::Number]) {
+            Ok(self.previous().literal.unwrap_or(Object::Nil))
+        } else {
+            Err(LaxError::parse_error(self.peek(), "Expect pattern."))
+        }
+    }
+
     fn consume(&mut self, t_type: TokenType, message: &str) -> Result<Token, LaxError> {
         if self.check(t_type) {
             return Ok(self.advance());
@@ -150,6 +667,7 @@ impl Parser {
         Parser::error(&self.peek(), message)
     }
 
+    #[allow(dead_code)]
     fn synchronize(&mut self) {
         self.advance();
 
@@ -183,7 +701,7 @@ impl Parser {
 
     fn match_token(&mut self, types: &[TokenType]) -> bool {
         for t in types {
-            if self.check(t.clone()) {
+            if self.check(*t) {
                 self.advance();
                 return true;
             }
@@ -199,6 +717,13 @@ impl Parser {
         }
     }
 
+    fn check_next(&self, t_type: TokenType) -> bool {
+        match self.tokens.get(self.current + 1) {
+            Some(token) => token.is(t_type),
+            None => false,
+        }
+    }
+
     fn advance(&mut self) -> Token {
         if !self.is_at_end() {
             self.current += 1;
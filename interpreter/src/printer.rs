@@ -1,8 +1,17 @@
-use crate::expr::{BinaryExpr, Expr, ExprVisitor, GroupingExpr, LiteralExpr, UnaryExpr};
+use crate::expr::{
+    BinaryExpr, Expr, ExprVisitor, GetExpr, GroupingExpr, InfixExpr, LiteralExpr, MatchExpr,
+    Pattern, SetExpr, SliceExpr, ThrowExpr, TryExpr, TupleExpr, UnaryExpr, VariableExpr,
+};
 use crate::LaxError;
 
 pub struct AstPrinter;
 
+impl Default for AstPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AstPrinter {
     pub fn print(&self, expr: &Expr) -> Result<String, LaxError> {
         expr.accept(self)
@@ -12,7 +21,7 @@ impl AstPrinter {
         Self {}
     }
 
-    fn parenthesize(&self, name: &String, exprs: &[&Box<Expr>]) -> Result<String, LaxError> {
+    fn parenthesize(&self, name: &String, exprs: &[&Expr]) -> Result<String, LaxError> {
         let mut builder = format!("({name}");
 
         for expr in exprs {
@@ -23,6 +32,24 @@ impl AstPrinter {
 
         Ok(builder)
     }
+
+    fn pattern(&self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Wildcard => "_".to_string(),
+            Pattern::Literal(value) => value.to_string(),
+            Pattern::Range(range) => range.to_string(),
+            Pattern::Type(name) => format!("is {}", name.lexeme),
+            Pattern::Binding(name) => name.lexeme.clone(),
+            Pattern::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| self.pattern(e)).collect();
+                if elements.len() == 1 {
+                    format!("({},)", elements[0])
+                } else {
+                    format!("({})", elements.join(", "))
+                }
+            }
+        }
+    }
 }
 
 impl ExprVisitor<String> for AstPrinter {
@@ -45,4 +72,76 @@ impl ExprVisitor<String> for AstPrinter {
     fn visit_unary_expr(&self, expr: &UnaryExpr) -> Result<String, LaxError> {
         self.parenthesize(&expr.operator.lexeme, &[&expr.right])
     }
+
+    fn visit_variable_expr(&self, expr: &VariableExpr) -> Result<String, LaxError> {
+        Ok(expr.name.lexeme.clone())
+    }
+
+    fn visit_match_expr(&self, expr: &MatchExpr) -> Result<String, LaxError> {
+        let mut builder = format!("(match {}", expr.value.accept(self)?);
+
+        for arm in &expr.arms {
+            builder = format!("{builder} ({}", self.pattern(&arm.pattern));
+            if let Some(guard) = &arm.guard {
+                builder = format!("{builder} if {}", guard.accept(self)?);
+            }
+            builder = format!("{builder} => {})", arm.body.accept(self)?);
+        }
+
+        Ok(format!("{builder})"))
+    }
+
+    fn visit_get_expr(&self, expr: &GetExpr) -> Result<String, LaxError> {
+        self.parenthesize(&format!(". {}", expr.name.lexeme), &[&expr.object])
+    }
+
+    fn visit_slice_expr(&self, expr: &SliceExpr) -> Result<String, LaxError> {
+        let mut bounds = vec![];
+        for bound in [&expr.start, &expr.end, &expr.step] {
+            bounds.push(match bound {
+                Some(bound) => bound.accept(self)?,
+                None => String::new(),
+            });
+        }
+        Ok(format!(
+            "(slice {} {})",
+            expr.object.accept(self)?,
+            bounds.join(":")
+        ))
+    }
+
+    fn visit_throw_expr(&self, expr: &ThrowExpr) -> Result<String, LaxError> {
+        self.parenthesize(&"throw".to_string(), &[&expr.value])
+    }
+
+    fn visit_try_expr(&self, expr: &TryExpr) -> Result<String, LaxError> {
+        let mut builder = format!("(try {}", expr.body.accept(self)?);
+
+        if let Some(catch) = &expr.catch {
+            builder = format!(
+                "{builder} (catch {} {})",
+                catch.name.lexeme,
+                catch.body.accept(self)?
+            );
+        }
+        if let Some(finally) = &expr.finally {
+            builder = format!("{builder} (finally {})", finally.accept(self)?);
+        }
+
+        Ok(format!("{builder})"))
+    }
+
+    fn visit_tuple_expr(&self, expr: &TupleExpr) -> Result<String, LaxError> {
+        let elements: Vec<&Expr> = expr.elements.iter().collect();
+        self.parenthesize(&"tuple".to_string(), &elements)
+    }
+
+    fn visit_set_expr(&self, expr: &SetExpr) -> Result<String, LaxError> {
+        let elements: Vec<&Expr> = expr.elements.iter().collect();
+        self.parenthesize(&"set".to_string(), &elements)
+    }
+
+    fn visit_infix_expr(&self, expr: &InfixExpr) -> Result<String, LaxError> {
+        self.parenthesize(&expr.operator.lexeme, &[&expr.left, &expr.right])
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::{LaxError, Object, Token, TokenType};
 
 pub struct Scanner {
@@ -6,6 +8,8 @@ pub struct Scanner {
     pub start: usize,
     pub current: usize,
     pub line: usize,
+    /// Symbols declared with `infix` so far.
+    pub operators: HashSet<String>,
 }
 
 impl Scanner {
@@ -16,6 +20,7 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
+            operators: HashSet::new(),
         }
     }
 
@@ -52,46 +57,23 @@ impl Scanner {
             ')' => self.add_token(TokenType::RightParent, None),
             '{' => self.add_token(TokenType::LeftBrace, None),
             '}' => self.add_token(TokenType::RightBrace, None),
+            '[' => self.add_token(TokenType::LeftBracket, None),
+            ']' => self.add_token(TokenType::RightBracket, None),
             ',' => self.add_token(TokenType::Comma, None),
-            '.' => self.add_token(TokenType::Dot, None),
-            '-' => self.add_token(TokenType::Minus, None),
-            '+' => self.add_token(TokenType::Plus, None),
-            ';' => self.add_token(TokenType::Semicolon, None),
-            '*' => self.add_token(TokenType::Star, None),
-            '!' => {
-                let tok = if self.matches('=') {
-                    TokenType::BangEqual
-                } else if self.matches_str("in") {
-                    TokenType::BangIn
-                } else {
-                    TokenType::Bang
-                };
-                self.add_token(tok, None);
-            }
-            '=' => {
-                let tok = if self.matches('=') {
-                    TokenType::EqualEqual
-                } else {
-                    TokenType::Equal
-                };
-                self.add_token(tok, None);
-            }
-            '>' => {
-                let tok = if self.matches('=') {
-                    TokenType::GreaterEqual
-                } else {
-                    TokenType::Greater
-                };
-                self.add_token(tok, None);
-            }
-            '<' => {
-                let tok = if self.matches('=') {
-                    TokenType::LessEqual
+            ':' => self.add_token(TokenType::Colon, None),
+            '.' => {
+                let tok = if self.matches('.') {
+                    if self.matches('=') {
+                        TokenType::DotDotEqual
+                    } else {
+                        TokenType::DotDot
+                    }
                 } else {
-                    TokenType::Less
+                    TokenType::Dot
                 };
                 self.add_token(tok, None);
             }
+            ';' => self.add_token(TokenType::Semicolon, None),
             '/' => {
                 if self.matches('/') {
                     // Comment goes until the end of the line
@@ -106,14 +88,22 @@ impl Scanner {
                     // block comment start
                     self.scan_comment()?;
                 } else {
-                    self.add_token(TokenType::Slash, None);
+                    self.operator();
                 }
             }
+            _ if Scanner::is_symbol(c) => {
+                self.operator();
+            }
             ' ' | '\r' | '\t' => {}
             '\n' => self.line += 1,
             '"' => {
                 self.string()?;
             }
+            'r' if self.peek() == Some('"') => {
+                // A raw string. The prefix is not part of the value.
+                self.advance();
+                self.string()?;
+            }
             '0'..='9' => {
                 self.number()?;
             }
@@ -169,7 +159,14 @@ impl Scanner {
             .push(Token::new(t_type, text, literal, self.line));
     }
 
+    /// Scans a string after its opening quote, or a triple-quoted string if two
+    /// more quotes follow.
     fn string(&mut self) -> Result<(), LaxError> {
+        if self.matches_str("\"\"") {
+            return self.multiline_string();
+        }
+        let opening = self.current;
+
         while let Some(ch) = self.peek() {
             match ch {
                 '"' => {
@@ -192,14 +189,159 @@ impl Scanner {
         // The closing ".
         self.advance();
 
-        // TODO: Handle Escape Sequences
-        let value: String = self.source[self.start + 1..self.current - 1]
-            .iter()
-            .collect();
+        // TODO: Handle Escape Sequences, but not in raw strings
+        let value: String = self.source[opening..self.current - 1].iter().collect();
         self.add_token(TokenType::String, Some(Object::Str(value)));
         Ok(())
     }
 
+    /// Scans a `"""` string. Its value is the text between the quotes without the
+    /// indentation common to all lines.
+    fn multiline_string(&mut self) -> Result<(), LaxError> {
+        let opening = self.current;
+
+        while !self.matches_str("\"\"\"") {
+            match self.peek() {
+                Some('\n') => self.line += 1,
+                Some(_) => {}
+                None => {
+                    return Err(LaxError::error(
+                        self.line,
+                        "Unterminated string".to_string(),
+                    ))
+                }
+            }
+            self.advance();
+        }
+
+        let text: String = self.source[opening..self.current - 3].iter().collect();
+        self.add_token(TokenType::String, Some(Object::Str(Scanner::dedent(&text))));
+        Ok(())
+    }
+
+    /// Removes the indentation common to all non-blank lines of `text`.
+    ///
+    /// A line break right after the opening quotes and the blank line before the
+    /// closing quotes are dropped, so the quotes can stand on their own lines.
+    fn dedent(text: &str) -> String {
+        let mut lines: Vec<&str> = text.split('\n').collect();
+        if lines.len() > 1 && lines[0].trim().is_empty() {
+            lines.remove(0);
+        }
+        if lines.len() > 1 && lines[lines.len() - 1].trim().is_empty() {
+            lines.pop();
+        }
+
+        let is_indent = |ch: char| ch == ' ' || ch == '\t';
+        let indent = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().take_while(|&ch| is_indent(ch)).count())
+            .min()
+            .unwrap_or(0);
+
+        lines
+            .iter()
+            .map(|line| line.chars().skip(indent).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Scans a run of symbol characters.
+    ///
+    /// The longest prefix of the run that is a built-in or a declared operator
+    /// becomes the token and the rest is scanned again, so `1==-1` still reads as
+    /// `1 == -1`. A run directly after `infix <precedence>` declares a new operator.
+    fn operator(&mut self) {
+        while let Some(ch) = self.peek() {
+            let comment = ch == '/' && matches!(self.peek_next(), Some('/') | Some('*'));
+            if !Scanner::is_symbol(ch) || comment {
+                break;
+            }
+            self.advance();
+        }
+        let run: String = self.source[self.start..self.current].iter().collect();
+
+        if run == "!" && self.matches_str("in") {
+            self.add_token(TokenType::BangIn, None);
+            return;
+        }
+        if Scanner::builtin_operator(&run).is_none() && self.declares_operator() {
+            self.operators.insert(run);
+            self.add_token(TokenType::Operator, None);
+            return;
+        }
+
+        let end = self.current;
+        for current in (self.start + 1..=end).rev() {
+            let lexeme: String = self.source[self.start..current].iter().collect();
+            let t_type = Scanner::builtin_operator(&lexeme).or_else(|| {
+                self.operators
+                    .contains(&lexeme)
+                    .then_some(TokenType::Operator)
+            });
+            if let Some(t_type) = t_type {
+                self.current = current;
+                self.add_token(t_type, None);
+                return;
+            }
+        }
+
+        // Unknown operators are reported by the parser.
+        self.add_token(TokenType::Operator, None);
+    }
+
+    /// Returns `true` if the tokens so far end in `infix <precedence>`.
+    fn declares_operator(&self) -> bool {
+        matches!(
+            self.tokens.as_slice(),
+            [.., keyword, precedence]
+                if keyword.is(TokenType::Infix) && precedence.is(TokenType::Number)
+        )
+    }
+
+    fn builtin_operator(lexeme: &str) -> Option<TokenType> {
+        match lexeme {
+            "-" => Some(TokenType::Minus),
+            "+" => Some(TokenType::Plus),
+            "*" => Some(TokenType::Star),
+            "/" => Some(TokenType::Slash),
+            "|" => Some(TokenType::Pipe),
+            "&" => Some(TokenType::Ampersand),
+            "!" => Some(TokenType::Bang),
+            "!=" => Some(TokenType::BangEqual),
+            "=" => Some(TokenType::Equal),
+            "==" => Some(TokenType::EqualEqual),
+            "=>" => Some(TokenType::FatArrow),
+            ">" => Some(TokenType::Greater),
+            ">=" => Some(TokenType::GreaterEqual),
+            "<" => Some(TokenType::Less),
+            "<=" => Some(TokenType::LessEqual),
+            _ => None,
+        }
+    }
+
+    fn is_symbol(ch: char) -> bool {
+        matches!(
+            ch,
+            '+' | '-'
+                | '*'
+                | '/'
+                | '<'
+                | '>'
+                | '='
+                | '!'
+                | '&'
+                | '|'
+                | '^'
+                | '%'
+                | '~'
+                | '?'
+                | '@'
+                | '$'
+        )
+    }
+
     fn scan_comment(&mut self) -> Result<(), LaxError> {
         loop {
             match self.peek() {
@@ -293,6 +435,14 @@ impl Scanner {
             "print" => Some(TokenType::Print),
             "is" => Some(TokenType::Is),
             "in" => Some(TokenType::In),
+            "match" => Some(TokenType::Match),
+            "throw" => Some(TokenType::Throw),
+            "try" => Some(TokenType::Try),
+            "catch" => Some(TokenType::Catch),
+            "finally" => Some(TokenType::Finally),
+            "infix" => Some(TokenType::Infix),
+            "macro" => Some(TokenType::Macro),
+            "const" => Some(TokenType::Const),
             "return" => Some(TokenType::Return),
             _ => None,
         }
@@ -300,7 +450,7 @@ impl Scanner {
 
     fn is_alpha_numeric(ch: Option<char>) -> bool {
         if let Some(ch) = ch {
-            ch.is_ascii_alphanumeric()
+            ch.is_ascii_alphanumeric() || ch == '_'
         } else {
             false
         }
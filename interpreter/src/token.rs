@@ -8,13 +8,18 @@ pub enum TokenType {
     RightParent,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
     Semicolon,
     Slash,
     Star,
+    Pipe,
+    Ampersand,
 
     // One or two character tokens
     Bang,
@@ -25,6 +30,9 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    FatArrow,
+    DotDot,
+    DotDotEqual,
 
     // Literals
     String,
@@ -32,6 +40,8 @@ pub enum TokenType {
     Number,
 
     BangIn,
+    /// A symbol declared with `infix`, such as `<+>`.
+    Operator,
 
     // Keywords
     And,
@@ -57,6 +67,14 @@ pub enum TokenType {
     Break,
     Is,
     In,
+    Match,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Infix,
+    Macro,
+    Const,
 
     EOF,
 }
@@ -69,6 +87,44 @@ pub enum Object {
     Nil,
     True,
     False,
+    Error(Box<ErrorObject>),
+    Range(Range),
+    Tuple(Vec<Object>),
+    /// Distinct values in insertion order.
+    Set(Vec<Object>),
+}
+
+/// A numeric range, `start..end` or `start..=end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: f64,
+    pub end: f64,
+    pub inclusive: bool,
+}
+
+impl Range {
+    pub fn contains(&self, value: f64) -> bool {
+        if self.inclusive {
+            self.start <= value && value <= self.end
+        } else {
+            self.start <= value && value < self.end
+        }
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{}", self.start, operator, self.end)
+    }
+}
+
+/// The value a `catch` clause receives for a built-in runtime error.
+#[derive(Debug, Clone)]
+pub struct ErrorObject {
+    pub kind: String,
+    pub message: String,
+    pub line: usize,
 }
 
 impl From<bool> for Object {
@@ -86,6 +142,20 @@ impl fmt::Display for Object {
             Object::True => write!(f, "true"),
             Object::False => write!(f, "false"),
             Object::Bool(b) => write!(f, "{}", b),
+            Object::Error(err) => write!(f, "{}: {}", err.kind, err.message),
+            Object::Range(range) => write!(f, "{}", range),
+            Object::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                if elements.len() == 1 {
+                    write!(f, "({},)", elements[0])
+                } else {
+                    write!(f, "({})", elements.join(", "))
+                }
+            }
+            Object::Set(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "{{{}}}", elements.join(", "))
+            }
         }
     }
 }
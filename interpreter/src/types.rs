@@ -0,0 +1,116 @@
+//! This module contains the static types used by type annotations and the checker
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::token::Object;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// The type of unannotated code, compatible with every other type.
+    Any,
+    /// The type of expressions that never produce a value, such as `throw`.
+    Never,
+    Number,
+    String,
+    Bool,
+    Nil,
+    Range,
+    Tuple,
+    Set,
+    Error,
+    /// `Option<T>`, a `T` or `nil`.
+    Option(Box<Type>),
+    /// A type parameter of an infix declaration, such as `T`.
+    Param(String),
+}
+
+impl Type {
+    /// Returns the type named `name` in an annotation, other than `Option<T>`.
+    pub fn named(name: &str) -> Option<Type> {
+        match name {
+            "Any" => Some(Type::Any),
+            "Number" => Some(Type::Number),
+            "String" => Some(Type::String),
+            "Bool" => Some(Type::Bool),
+            "Nil" => Some(Type::Nil),
+            "Range" => Some(Type::Range),
+            "Tuple" => Some(Type::Tuple),
+            "Set" => Some(Type::Set),
+            "Error" => Some(Type::Error),
+            _ => None,
+        }
+    }
+
+    pub fn of(value: &Object) -> Type {
+        match value {
+            Object::Num(_) => Type::Number,
+            Object::Str(_) => Type::String,
+            Object::Bool(_) | Object::True | Object::False => Type::Bool,
+            Object::Nil => Type::Nil,
+            Object::Error(_) => Type::Error,
+            Object::Range(_) => Type::Range,
+            Object::Tuple(_) => Type::Tuple,
+            Object::Set(_) => Type::Set,
+        }
+    }
+
+    /// Replaces the type parameters in `self` with their `arguments`.
+    ///
+    /// Parameters without argument become [`Type::Any`].
+    pub fn instantiate(&self, arguments: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::Param(name) => arguments.get(name).cloned().unwrap_or(Type::Any),
+            Type::Option(inner) => Type::Option(Box::new(inner.instantiate(arguments))),
+            _ => self.clone(),
+        }
+    }
+
+    /// Checks if a value of type `found` may be used where `self` is expected.
+    pub fn accepts(&self, found: &Type) -> bool {
+        match (self, found) {
+            (Type::Any, _) | (_, Type::Any) | (_, Type::Never) => true,
+            (Type::Option(expected), Type::Option(found)) => expected.accepts(found),
+            (Type::Option(expected), found) => *found == Type::Nil || expected.accepts(found),
+            (expected, found) => expected == found,
+        }
+    }
+
+    /// Returns the type of a value that is either of type `self` or of type `other`.
+    pub fn join(&self, other: &Type) -> Type {
+        match (self, other) {
+            (Type::Never, other) | (other, Type::Never) => other.clone(),
+            (left, right) if left == right => left.clone(),
+            (Type::Nil, Type::Option(inner)) | (Type::Option(inner), Type::Nil) => {
+                Type::Option(inner.clone())
+            }
+            (Type::Nil, other) | (other, Type::Nil) if *other != Type::Any => {
+                Type::Option(Box::new(other.clone()))
+            }
+            (Type::Option(inner), other) | (other, Type::Option(inner)) if **inner == *other => {
+                Type::Option(inner.clone())
+            }
+            _ => Type::Any,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "Any"),
+            Type::Never => write!(f, "Never"),
+            Type::Number => write!(f, "Number"),
+            Type::String => write!(f, "String"),
+            Type::Bool => write!(f, "Bool"),
+            Type::Nil => write!(f, "Nil"),
+            Type::Range => write!(f, "Range"),
+            Type::Tuple => write!(f, "Tuple"),
+            Type::Set => write!(f, "Set"),
+            Type::Error => write!(f, "Error"),
+            Type::Option(inner) => write!(f, "Option<{}>", inner),
+            Type::Param(name) => write!(f, "{}", name),
+        }
+    }
+}